@@ -0,0 +1,45 @@
+//! Counts the allocations of adding a context, that is a string literal, with and without copying it.
+//!
+//! Run with `cargo bench --bench context`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use errors_with_context::WithContext;
+
+const ITERATIONS: usize = 10_000;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn main() {
+    bench("with_err_context", || Err::<(), _>(io::Error::from(io::ErrorKind::NotFound)).with_err_context("Failed to read file"));
+    bench("with_err_context_static", || {
+        Err::<(), _>(io::Error::from(io::ErrorKind::NotFound)).with_err_context_static("Failed to read file")
+    });
+}
+
+fn bench<T>(name: &str, mut f: impl FnMut() -> T) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let allocations = (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / ITERATIONS as f64;
+    println!("{name:>24}: {allocations} allocations per error");
+}
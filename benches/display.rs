@@ -0,0 +1,31 @@
+//! Formats and serializes a deep chain, which walks every frame without downcasting the nested [ErrorMessage]s.
+//!
+//! Run with `cargo bench --bench display`.
+
+use std::hint::black_box;
+use std::io;
+use std::time::Instant;
+use errors_with_context::ErrorMessage;
+
+const FRAMES: usize = 1_000;
+const ITERATIONS: usize = 1_000;
+
+fn main() {
+    let mut error = ErrorMessage::with_context("Failed to read file", io::Error::from(io::ErrorKind::NotFound));
+    for index in 1..FRAMES {
+        error = error.context(format!("Failed in layer {index}"));
+    }
+
+    bench("Display", || error.to_string().len());
+    bench("messages", || error.messages().count());
+    bench("to_json_string", || error.to_json_string().len());
+}
+
+fn bench(name: &str, mut f: impl FnMut() -> usize) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let per_frame = start.elapsed() / (ITERATIONS * FRAMES) as u32;
+    println!("{name:>16}: {per_frame:?} per frame");
+}
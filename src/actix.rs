@@ -0,0 +1,29 @@
+use crate::error_message::ErrorMessage;
+use actix_web::http::StatusCode;
+use actix_web::http::header::ContentType;
+use actix_web::{HttpResponse, ResponseError};
+
+/// Responds with the [status](ErrorMessage::status) of the error, or else `500 Internal Server Error`,
+/// and a JSON body with the outermost message and the messages of the whole chain:
+///
+/// ```json
+/// {"error":"Failed to load the user","chain":["Failed to load the user","Connection refused"]}
+/// ```
+///
+/// The chain is left out, if [hide_error_internals](crate::hide_error_internals) is set.
+/// With the `tracing` feature, the whole chain is also [recorded](ErrorMessage::record).
+impl ResponseError for ErrorMessage {
+    fn status_code(&self) -> StatusCode {
+        // actix-web still uses version 0.2 of the http crate, so the status is converted by its code
+        self.status()
+            .and_then(|status| StatusCode::from_u16(status.as_u16()).ok())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.responded();
+        HttpResponse::build(self.status_code())
+            .content_type(ContentType::json())
+            .body(self.response_body())
+    }
+}
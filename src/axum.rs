@@ -0,0 +1,24 @@
+use crate::error_message::ErrorMessage;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+
+/// Responds with the [status](ErrorMessage::status) of the error, or else `500 Internal Server Error`,
+/// and a JSON body with the outermost message and the messages of the whole chain:
+///
+/// ```json
+/// {"error":"Failed to load the user","chain":["Failed to load the user","Connection refused"]}
+/// ```
+///
+/// The chain is left out, if [hide_error_internals](crate::hide_error_internals) is set.
+/// With the `tracing` feature, the whole chain is also [recorded](ErrorMessage::record).
+impl IntoResponse for ErrorMessage {
+    fn into_response(self) -> Response {
+        self.responded();
+        (
+            self.status().unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            [(header::CONTENT_TYPE, "application/json")],
+            self.response_body(),
+        )
+            .into_response()
+    }
+}
@@ -1,4 +1,5 @@
 use crate::ErrorMessage;
+use std::borrow::Cow;
 
 /// This trait allows one to turn [bools](bool) into [ErrorMessages](ErrorMessage),
 /// when they have a certain value.
@@ -92,7 +93,7 @@ pub trait BooleanErrors {
     /// #     let  string = func().unwrap_err().to_string();
     /// #     assert_eq!(string, "Expected file 'test.file' to exist!");
     /// # }
-    fn error_dyn_if_false(self, context: impl FnOnce() -> String) -> Result<bool, ErrorMessage>;
+    fn error_dyn_if_false<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<bool, ErrorMessage>;
 
     /// If the [bool] is false, return it `Ok(bool)`.  
     /// If the [bool] is true, compute the context and return an `Err(ErrorMessage)`.
@@ -112,10 +113,11 @@ pub trait BooleanErrors {
     /// #     let  string = func().unwrap_err().to_string();
     /// #     assert_eq!(string, "Expected file 'test.file' to exist!");
     /// # }
-    fn error_dyn_if_true(self, context: impl FnOnce() -> String) -> Result<bool, ErrorMessage>;
+    fn error_dyn_if_true<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<bool, ErrorMessage>;
 }
 
 impl BooleanErrors for bool {
+    #[cfg_attr(feature = "location", track_caller)]
     fn error_if_false(self, context: impl AsRef<str>) -> Result<bool, ErrorMessage> {
         if self {
             Ok(self)
@@ -124,6 +126,7 @@ impl BooleanErrors for bool {
         }
     }
 
+    #[cfg_attr(feature = "location", track_caller)]
     fn error_if_true(self, context: impl AsRef<str>) -> Result<bool, ErrorMessage> {
         if self {
             Err(ErrorMessage::new(context.as_ref()))
@@ -132,17 +135,19 @@ impl BooleanErrors for bool {
         }
     }
 
-    fn error_dyn_if_false(self, context: impl FnOnce() -> String) -> Result<bool, ErrorMessage> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn error_dyn_if_false<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<bool, ErrorMessage> {
         if self {
             Ok(self)
         } else {
-            Err(ErrorMessage::new(context()))
+            Err(ErrorMessage::new_static(context()))
         }
     }
 
-    fn error_dyn_if_true(self, context: impl FnOnce() -> String) -> Result<bool, ErrorMessage> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn error_dyn_if_true<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<bool, ErrorMessage> {
         if self {
-            Err(ErrorMessage::new(context()))
+            Err(ErrorMessage::new_static(context()))
         } else {
             Ok(self)
         }
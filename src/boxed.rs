@@ -0,0 +1,67 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+use std::error::Error;
+
+/// [WithContext](crate::WithContext) for `Result<T, Box<dyn Error>>` and its variants with [Send] and [Sync].
+///
+/// A boxed `dyn Error` doesn't implement [Error] itself, so [WithContext](crate::WithContext) can't be implemented for it
+/// without conflicting with its implementation for all errors.
+/// This trait has the same methods, so it works the same way, once it is imported, for example with the [prelude](crate::prelude).
+/// The box becomes the cause as it is, so the error inside of it can still be [downcast](Error::downcast_ref).
+///
+/// With the `send` feature, all causes have to be [Send], so it isn't implemented for `Result<T, Box<dyn Error>>`.
+pub trait BoxedWithContext<T> {
+    /// Like [with_err_context](crate::WithContext::with_err_context), but for boxed errors.
+    ///
+    /// ```rust
+    /// # use std::{error::Error, io};
+    /// use errors_with_context::prelude::*;
+    /// fn read_config() -> Result<String, Box<dyn Error + Send + Sync>> {
+    ///     Err(Box::new(io::Error::from(io::ErrorKind::NotFound)))
+    /// }
+    /// let error = read_config().with_err_context("Failed to read config").unwrap_err();
+    /// assert_eq!(error.to_string(), "Failed to read config\n  caused by: Kind(NotFound)");
+    /// assert!(error.source().unwrap().downcast_ref::<io::Error>().is_some());
+    /// ```
+    fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+
+    /// Like [with_dyn_err_context](crate::WithContext::with_dyn_err_context), but for boxed errors.
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage>;
+
+    /// Like [with_err_context_id](crate::WithContext::with_err_context_id), but for boxed errors.
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage>;
+}
+
+/// Implements [BoxedWithContext] for a boxed error, that can be turned into the box of a cause without boxing it again
+macro_rules! boxed_with_context {
+    ($($boxed:ty),* $(,)?) => {
+        $(
+            impl<T> BoxedWithContext<T> for Result<T, $boxed> {
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
+                    match self {
+                        Ok(value) => Ok(value),
+                        Err(error) => Err(ErrorMessage::with_boxed_context(context.to_string().into(), error, None)),
+                    }
+                }
+
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
+                    match self {
+                        Ok(value) => Ok(value),
+                        Err(error) => Err(ErrorMessage::with_boxed_context(context().into(), error, None)),
+                    }
+                }
+
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
+                    self.with_err_context(context).map_err(|error| error.with_id(id))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(not(feature = "send"))]
+boxed_with_context!(Box<dyn Error>);
+boxed_with_context!(Box<dyn Error + Send>, Box<dyn Error + Send + Sync>);
@@ -0,0 +1,211 @@
+use crate::error_message::{BoxedError, ErrorMessage, Frame};
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::error::Error;
+use std::io;
+use std::iter::FusedIterator;
+
+/// An iterator over an [ErrorMessage] and all of its causes.
+///
+/// Created by [ErrorMessage::chain].
+#[derive(Clone)]
+pub struct Chain<'a> {
+    next: Option<Frame<'a>>,
+    /// The number of frames, that are left, which is only counted, when it is asked for the first time
+    remaining: Cell<Option<usize>>,
+}
+
+impl ErrorMessage {
+    /// Iterate over this error and then each of its causes in order, down to the foreign error at the end of the chain.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::TimedOut))
+    ///     .with_err_context("Failed to read response")
+    ///     .with_err_context("Failed to fetch data");
+    /// let error = result.unwrap_err();
+    /// assert_eq!(error.chain().len(), 3);
+    /// let should_retry = error.chain().any(|error| error.is::<io::Error>());
+    /// # assert!(should_retry);
+    /// ```
+    pub fn chain(&self) -> Chain<'_> {
+        Chain { next: Some(Frame::Message(self)), remaining: Cell::new(None) }
+    }
+
+    /// Returns the [ErrorKind](io::ErrorKind) of the first [io::Error] in the chain, for example to decide whether to retry.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::TimedOut))
+    ///     .with_err_context("Failed to connect")
+    ///     .with_err_context("Failed to fetch data");
+    /// assert_eq!(result.unwrap_err().io_kind(), Some(io::ErrorKind::TimedOut));
+    /// assert_eq!(ErrorMessage::new("Invalid input").io_kind(), None);
+    /// ```
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        self.downcast_ref::<io::Error>().map(io::Error::kind)
+    }
+
+    /// Returns the [raw OS error code](io::Error::raw_os_error) of the first [io::Error] in the chain, if it has one.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from_raw_os_error(2))
+    ///     .with_err_context("Failed to read file");
+    /// assert_eq!(result.unwrap_err().os_error_code(), Some(2));
+    /// ```
+    pub fn os_error_code(&self) -> Option<i32> {
+        self.downcast_ref::<io::Error>().and_then(io::Error::raw_os_error)
+    }
+
+    /// Returns the first error of type `E` in the chain, starting with this error itself.
+    /// Nested [ErrorMessages](ErrorMessage) are searched as well.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_err_context("Failed to read file")
+    ///     .with_err_context("Failed to load configuration");
+    /// let error = result.unwrap_err();
+    /// let kind = error.downcast_ref::<io::Error>().map(io::Error::kind);
+    /// assert_eq!(kind, Some(io::ErrorKind::NotFound));
+    /// ```
+    pub fn downcast_ref<E: Error + 'static>(&self) -> Option<&E> {
+        self.chain().find_map(|error| error.downcast_ref::<E>())
+    }
+
+    /// Consumes the [ErrorMessage] and returns the first error of type `E` in the chain by value,
+    /// starting with this error itself.
+    /// All frames above it are dropped.
+    ///
+    /// If there is no error of type `E` in the chain, the unchanged [ErrorMessage] is returned instead.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_err_context("Failed to read file");
+    /// let error = result.unwrap_err();
+    /// let error = error.downcast::<std::fmt::Error>().unwrap_err();
+    /// let error: io::Error = error.downcast().unwrap();
+    /// assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    /// ```
+    pub fn downcast<E: Error + 'static>(self) -> Result<E, ErrorMessage> {
+        if !self.is::<E>() {
+            return Err(self);
+        }
+        let mut current: BoxedError = Box::new(self);
+        loop {
+            current = match current.downcast::<E>() {
+                Ok(error) => return Ok(*error),
+                Err(current) => match current.downcast::<ErrorMessage>() {
+                    Ok(mut error) => error.cause.take().expect("The chain contains an error of type E below this frame").into_boxed(),
+                    Err(_) => unreachable!("Only ErrorMessages have causes in the chain"),
+                },
+            };
+        }
+    }
+
+    /// Returns true, if any error in the chain is of type `E`, starting with this error itself.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::TimedOut))
+    ///     .with_err_context("Failed to fetch data");
+    /// let error = result.unwrap_err();
+    /// assert!(error.is::<io::Error>());
+    /// assert!(!error.is::<std::fmt::Error>());
+    /// ```
+    pub fn is<E: Error + 'static>(&self) -> bool {
+        self.chain().any(|error| error.is::<E>())
+    }
+
+    /// Returns the number of frames in the chain, including the foreign error at its end.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_err_context("Failed to read file");
+    /// assert_eq!(result.unwrap_err().depth(), 2);
+    /// assert_eq!(ErrorMessage::new("No cause").depth(), 1);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.chain().count()
+    }
+
+    /// Iterate over the message of every frame, outermost first.
+    /// A foreign error at the end of the chain is formatted with Debug, just like in the Display output.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_err_context("Failed to read file")
+    ///     .with_err_context("Failed to load configuration");
+    /// let error = result.unwrap_err();
+    /// let messages: Vec<_> = error.messages().collect();
+    /// assert_eq!(messages, ["Failed to load configuration", "Failed to read file", "Kind(NotFound)"]);
+    /// ```
+    pub fn messages(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.frames().map(|frame| match frame {
+            Frame::Message(error) => Cow::Borrowed(&*error.message),
+            Frame::Cause(cause) => Cow::Owned(format!("{cause:?}")),
+        })
+    }
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a (dyn Error + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame = self.next?;
+        self.next = frame.next_frame();
+        self.remaining.set(self.remaining.get().map(|remaining| remaining - 1));
+        Some(frame.as_error())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.get().unwrap_or_else(|| self.clone().count());
+        self.remaining.set(Some(len));
+        (len, Some(len))
+    }
+
+    fn count(self) -> usize {
+        if let Some(remaining) = self.remaining.get() {
+            return remaining;
+        }
+        let mut count = 0;
+        let mut next = self.next;
+        while let Some(frame) = next {
+            count += 1;
+            next = frame.next_frame();
+        }
+        count
+    }
+}
+
+impl ExactSizeIterator for Chain<'_> {}
+
+impl FusedIterator for Chain<'_> {}
@@ -0,0 +1,99 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+
+/// This trait adds context to the results of sending into a channel.
+///
+/// [SendError](std::sync::mpsc::SendError) and its relatives contain the value, that couldn't be sent,
+/// and only implement [Error](std::error::Error), if that value implements [Debug](std::fmt::Debug).
+/// Instead, the innermost frame of the chain says `channel closed`, `channel full` or `send timed out`,
+/// and the value is dropped or returned next to the [ErrorMessage].
+///
+/// It is implemented for the errors of [std::sync::mpsc] and, with the `crossbeam` feature, for those of
+/// [crossbeam-channel](https://crates.io/crates/crossbeam-channel).
+///
+/// ```rust
+/// # use std::sync::mpsc;
+/// use errors_with_context::prelude::*;
+/// struct Job;
+/// let (sender, receiver) = mpsc::channel();
+/// drop(receiver);
+/// let error = sender.send(Job).with_send_err_context("Failed to queue the job").unwrap_err();
+/// assert_eq!(error.to_string(), "Failed to queue the job\n  caused by: channel closed");
+/// ```
+pub trait SendErrors<T> {
+    /// Drop the value, that couldn't be sent, and return an [ErrorMessage] with the provided context.
+    fn with_send_err_context(self, context: impl ToString) -> Result<(), ErrorMessage>;
+
+    /// Like [with_send_err_context](SendErrors::with_send_err_context), but the context is only built in case of an error.
+    fn with_dyn_send_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<(), ErrorMessage>;
+
+    /// Like [with_send_err_context](SendErrors::with_send_err_context), but also return the value, that couldn't be sent,
+    /// so it can be sent again or somewhere else.
+    ///
+    /// ```rust
+    /// # use std::sync::mpsc;
+    /// use errors_with_context::prelude::*;
+    /// let (sender, _receiver) = mpsc::sync_channel(0);
+    /// let (error, job) = sender.try_send("backup").with_send_err_context_and_value("Failed to queue the job").unwrap_err();
+    /// assert_eq!(error.to_string(), "Failed to queue the job\n  caused by: channel full");
+    /// assert_eq!(job, "backup");
+    /// ```
+    fn with_send_err_context_and_value(self, context: impl ToString) -> Result<(), (ErrorMessage, T)>;
+}
+
+/// Implements [SendErrors] for a send error, whose variants are mapped to a message and the value, that wasn't sent
+macro_rules! send_errors {
+    ($($error:ty => |$name:ident| $parts:expr;)*) => {
+        $(
+            impl<T> SendErrors<T> for Result<(), $error> {
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_send_err_context(self, context: impl ToString) -> Result<(), ErrorMessage> {
+                    match self {
+                        Ok(()) => Ok(()),
+                        Err($name) => Err(ErrorMessage::new($parts.0).context(context)),
+                    }
+                }
+
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_dyn_send_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<(), ErrorMessage> {
+                    match self {
+                        Ok(()) => Ok(()),
+                        Err($name) => Err(ErrorMessage::new($parts.0).context_static(context())),
+                    }
+                }
+
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_send_err_context_and_value(self, context: impl ToString) -> Result<(), (ErrorMessage, T)> {
+                    match self {
+                        Ok(()) => Ok(()),
+                        Err($name) => {
+                            let (message, value) = $parts;
+                            Err((ErrorMessage::new(message).context(context), value))
+                        }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+send_errors! {
+    std::sync::mpsc::SendError<T> => |error| ("channel closed", error.0);
+    std::sync::mpsc::TrySendError<T> => |error| match error {
+        std::sync::mpsc::TrySendError::Full(value) => ("channel full", value),
+        std::sync::mpsc::TrySendError::Disconnected(value) => ("channel closed", value),
+    };
+}
+
+#[cfg(feature = "crossbeam")]
+send_errors! {
+    crossbeam_channel::SendError<T> => |error| ("channel closed", error.0);
+    crossbeam_channel::TrySendError<T> => |error| match error {
+        crossbeam_channel::TrySendError::Full(value) => ("channel full", value),
+        crossbeam_channel::TrySendError::Disconnected(value) => ("channel closed", value),
+    };
+    crossbeam_channel::SendTimeoutError<T> => |error| match error {
+        crossbeam_channel::SendTimeoutError::Timeout(value) => ("send timed out", value),
+        crossbeam_channel::SendTimeoutError::Disconnected(value) => ("channel closed", value),
+    };
+}
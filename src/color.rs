@@ -0,0 +1,62 @@
+use crate::error_message::{ErrorMessage, Frame};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+const RESET: &str = "\x1b[0m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const DIM: &str = "\x1b[2m";
+const YELLOW: &str = "\x1b[33m";
+
+impl ErrorMessage {
+    /// Render the chain with ANSI colors for terminals:
+    /// The outermost message is bold red, `caused by:` is dimmed and the root cause is yellow.
+    /// If the [Location](crate::Location) of a frame was recorded, it is shown as a dimmed suffix like `(at src/config.rs:42:10)`.
+    ///
+    /// The normal Display output and the serde output never contain colors.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("I/O Error").context("Failed to read file");
+    /// eprintln!("{}", error.display_colored());
+    /// # #[cfg(not(feature = "location"))]
+    /// # assert_eq!(
+    /// #     error.display_colored().to_string(),
+    /// #     "\x1b[1;31mFailed to read file\x1b[0m\n  \x1b[2mcaused by:\x1b[0m \x1b[33mI/O Error\x1b[0m"
+    /// # );
+    /// ```
+    pub fn display_colored(&self) -> impl Display + '_ {
+        Colored { error: self }
+    }
+}
+
+struct Colored<'a> {
+    error: &'a ErrorMessage,
+}
+
+impl Display for Colored<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let frames: Vec<Frame> = self.error.frames().collect();
+        for (index, frame) in frames.iter().enumerate() {
+            let color = if index == 0 {
+                BOLD_RED
+            } else if index + 1 == frames.len() {
+                YELLOW
+            } else {
+                ""
+            };
+            if index > 0 {
+                write!(f, "\n  {DIM}caused by:{RESET} ")?;
+            }
+            if color.is_empty() {
+                write!(f, "{frame}")?;
+            } else {
+                write!(f, "{color}{frame}{RESET}")?;
+            }
+            #[cfg(any(feature = "macros", feature = "location"))]
+            if let Some(location) = frame.message().and_then(ErrorMessage::location) {
+                write!(f, " {DIM}(at {location}){RESET}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// An error of type `E` with the contexts, that were added to it, for libraries, whose callers match on their error type.
+///
+/// The contexts are stored innermost first, the Display output shows them like the chain of an [ErrorMessage],
+/// outermost first and with the Debug output of the inner error at the end.
+/// [source](Error::source) returns the inner error.
+///
+/// At the boundary to the application, it can be turned into an [ErrorMessage] with the same chain.
+///
+/// ```rust
+/// # use std::io;
+/// use errors_with_context::prelude::*;
+/// use errors_with_context::ContextError;
+/// fn read_config() -> Result<String, ContextError<io::Error>> {
+///     Err(io::Error::from(io::ErrorKind::NotFound)).with_typed_err_context("Failed to read config")
+/// }
+///
+/// match read_config() {
+///     Err(ContextError { inner, .. }) if inner.kind() == io::ErrorKind::NotFound => {}
+///     _ => unreachable!(),
+/// }
+/// let error = ErrorMessage::from(read_config().unwrap_err());
+/// assert_eq!(error.to_string(), "Failed to read config\n  caused by: Kind(NotFound)");
+/// ```
+#[derive(Debug)]
+pub struct ContextError<E> {
+    /// The contexts, innermost first
+    pub context: Vec<String>,
+    /// The error, that caused all of the contexts
+    pub inner: E,
+}
+
+impl<E> ContextError<E> {
+    /// Add another context on top of the existing ones.
+    pub fn context(mut self, context: impl ToString) -> ContextError<E> {
+        self.context.push(context.to_string());
+        self
+    }
+}
+
+/// Wraps the error without any context
+impl<E> From<E> for ContextError<E> {
+    fn from(inner: E) -> ContextError<E> {
+        ContextError { context: Vec::new(), inner }
+    }
+}
+
+impl<E: Debug> Display for ContextError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for context in self.context.iter().rev() {
+            write!(f, "{context}\n  caused by: ")?;
+        }
+        write!(f, "{:?}", self.inner)
+    }
+}
+
+impl<E: Error + 'static> Error for ContextError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.inner)
+    }
+}
+
+/// Turns every context into a frame and the inner error into the cause at the end of the chain.
+/// Without any context, this is the same as [from_error](ErrorMessage::from_error).
+#[cfg(not(feature = "send"))]
+impl<E: Error + 'static> From<ContextError<E>> for ErrorMessage {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn from(error: ContextError<E>) -> ErrorMessage {
+        let mut contexts = error.context.into_iter();
+        let Some(innermost) = contexts.next() else {
+            return ErrorMessage::from_error(error.inner);
+        };
+        let mut message = ErrorMessage::with_boxed_context(innermost.into(), Box::new(error.inner), Some(std::any::type_name::<E>()));
+        for context in contexts {
+            message = message.context_static(context);
+        }
+        message
+    }
+}
+
+/// Turns every context into a frame and the inner error into the cause at the end of the chain.
+/// Without any context, this is the same as [from_error](ErrorMessage::from_error).
+#[cfg(feature = "send")]
+impl<E: Error + Send + 'static> From<ContextError<E>> for ErrorMessage {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn from(error: ContextError<E>) -> ErrorMessage {
+        let mut contexts = error.context.into_iter();
+        let Some(innermost) = contexts.next() else {
+            return ErrorMessage::from_error(error.inner);
+        };
+        let mut message = ErrorMessage::with_boxed_context(innermost.into(), Box::new(error.inner), Some(std::any::type_name::<E>()));
+        for context in contexts {
+            message = message.context_static(context);
+        }
+        message
+    }
+}
+
+/// [TypedWithContext] is implemented for every [Result] and adds context, while keeping the error type,
+/// see [ContextError].
+pub trait TypedWithContext<T, E> {
+    /// Wrap the error in a [ContextError] with the provided context.
+    ///
+    /// If the error already is a [ContextError], the context is stacked on top of its contexts instead.
+    /// Which of both happens, is decided by the type of the result, so it needs to be known,
+    /// for example from the return type of the function, when calling this on a [ContextError] a second time.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// use errors_with_context::ContextError;
+    /// let result: Result<(), ContextError<io::Error>> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_typed_err_context("Failed to read file")
+    ///     .with_typed_err_context("Failed to load configuration");
+    /// let error = result.unwrap_err();
+    /// assert_eq!(error.context, ["Failed to read file", "Failed to load configuration"]);
+    /// assert_eq!(error.inner.kind(), io::ErrorKind::NotFound);
+    /// ```
+    fn with_typed_err_context<X>(self, context: impl ToString) -> Result<T, ContextError<X>>
+    where
+        E: Into<ContextError<X>>;
+
+    /// Like [with_typed_err_context](TypedWithContext::with_typed_err_context), but the context is only built in case of an error.
+    fn with_dyn_typed_err_context<X, S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ContextError<X>>
+    where
+        E: Into<ContextError<X>>;
+}
+
+impl<T, E> TypedWithContext<T, E> for Result<T, E> {
+    fn with_typed_err_context<X>(self, context: impl ToString) -> Result<T, ContextError<X>>
+    where
+        E: Into<ContextError<X>>,
+    {
+        self.map_err(|error| error.into().context(context))
+    }
+
+    fn with_dyn_typed_err_context<X, S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ContextError<X>>
+    where
+        E: Into<ContextError<X>>,
+    {
+        self.map_err(|error| {
+            let mut error = error.into();
+            error.context.push(context().into().into_owned());
+            error
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use crate::error_message::ErrorMessage;
+
+/// Implements [From] for errors of the standard library, like [from_error](ErrorMessage::from_error) does it
+macro_rules! from_std_errors {
+    ($($error:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Converts a [", stringify!($error), "] like [from_error](ErrorMessage::from_error), so it can be thrown with `?`.")]
+            impl From<$error> for ErrorMessage {
+                #[cfg_attr(feature = "location", track_caller)]
+                fn from(error: $error) -> ErrorMessage {
+                    ErrorMessage::from_error(error)
+                }
+            }
+        )*
+    };
+}
+
+from_std_errors!(
+    std::io::Error,
+    std::num::ParseIntError,
+    std::num::ParseFloatError,
+    std::num::TryFromIntError,
+    std::str::Utf8Error,
+    std::string::FromUtf8Error,
+    std::time::SystemTimeError,
+    std::fmt::Error,
+);
@@ -0,0 +1,686 @@
+use crate::error_message::{ErrorMessage, Frame};
+use std::fmt;
+use std::io;
+use std::fmt::{Display, Formatter, Write};
+
+/// The number of frames [ErrorMessage::display_verbosity] shows at level 1
+const DEFAULT_DEPTH: usize = 3;
+
+impl ErrorMessage {
+    /// Write the chain into any [fmt::Write] sink, exactly like the [Display] implementation does.
+    ///
+    /// This is useful for custom reporters, that want to append to an existing buffer without
+    /// allocating an intermediate String.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("I/O Error").context("Failed to read file");
+    /// let mut report = String::from("error: ");
+    /// error.write_chain(&mut report).unwrap();
+    /// assert_eq!(report, "error: Failed to read file\n  caused by: I/O Error");
+    /// ```
+    pub fn write_chain<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        self.write_limited(usize::MAX, w)
+    }
+
+    /// Writes the first `depth` frames of the chain and how many causes were left out.
+    /// This is what the precision flag like `{:.2}` uses.
+    pub(crate) fn write_limited<W: fmt::Write>(&self, depth: usize, w: &mut W) -> fmt::Result {
+        write_layout(self, depth, Layout::DEFAULT, w)
+    }
+
+    /// Render the chain like the [Display] implementation, but with a different label in front of every cause,
+    /// for example to localize it.
+    ///
+    /// The label is followed by `": "`. An empty label leaves out both, so only the indentation remains.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+    ///     .with_err_context("Konfiguration konnte nicht geladen werden");
+    /// assert_eq!(result.unwrap_err().display_with_label("verursacht durch").to_string(), "\
+    /// Konfiguration konnte nicht geladen werden
+    ///   verursacht durch: Kind(UnexpectedEof)");
+    /// ```
+    pub fn display_with_label<'a>(&'a self, label: &'a str) -> impl Display + 'a {
+        WithLayout { error: self, layout: Layout { label, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain like the [Display] implementation, but write the foreign error at its end with its Display output
+    /// instead of its Debug output, for example `unexpected end of file` instead of `Kind(UnexpectedEof)`.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+    ///     .with_err_context("Failed to load configuration");
+    /// assert_eq!(result.unwrap_err().display_leaf_display().to_string(), "\
+    /// Failed to load configuration
+    ///   caused by: unexpected end of file");
+    /// ```
+    pub fn display_leaf_display(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { leaf: Leaf::Display, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain like the [Display] implementation, but write the foreign error at its end with its Display output
+    /// followed by its Debug output in brackets.
+    /// The Debug output is left out, if it is the same as the Display output.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+    ///     .with_err_context("Failed to load configuration");
+    /// assert_eq!(result.unwrap_err().display_leaf_both().to_string(), "\
+    /// Failed to load configuration
+    ///   caused by: unexpected end of file [Kind(UnexpectedEof)]");
+    /// ```
+    pub fn display_leaf_both(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { leaf: Leaf::Both, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain like the [Display] implementation, but write the type of the foreign error in front of it.
+    ///
+    /// The type is recorded, when the error is wrapped with [with_context](ErrorMessage::with_context)
+    /// or [set_cause](ErrorMessage::set_cause), because it can't be found out from the boxed error later.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+    ///     .with_err_context("Failed to load configuration");
+    /// assert_eq!(result.unwrap_err().display_type_names().to_string(), format!("\
+    /// Failed to load configuration
+    ///   caused by [{}]: Kind(UnexpectedEof)", std::any::type_name::<io::Error>()));
+    /// ```
+    pub fn display_type_names(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { type_names: true, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain like the [Display] implementation, but wrap long messages, so no line is longer than `width` characters.
+    /// Continuation lines are aligned under the start of their message and words, that don't fit on a line, are broken up.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Failed to fetch https://example.com/a/very/long/path")
+    ///     .context("Failed to download the latest release");
+    /// assert_eq!(error.display_wrapped(30).to_string(), "\
+    /// Failed to download the latest
+    /// release
+    ///   caused by: Failed to fetch
+    ///              https://example.c
+    ///              om/a/very/long/pa
+    ///              th");
+    /// ```
+    pub fn display_wrapped(&self, width: usize) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { width, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain like the [Display] implementation, but replace control characters and escape sequences
+    /// in messages with visible escapes like `\x1b`, so untrusted data in messages can't garble terminals or spoof log lines.
+    /// Only line breaks are kept, because they are aligned under their frame anyway.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Unexpected response '\x1b[31mOK\r'").context("Failed to log in");
+    /// assert_eq!(error.display_sanitized().to_string(), "\
+    /// Failed to log in
+    ///   caused by: Unexpected response '\\x1b[31mOK\\x0d'");
+    /// ```
+    pub fn display_sanitized(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { sanitize: true, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain like the [Display] implementation, but write the time every frame was
+    /// [created at](ErrorMessage::created_at) behind its message, as an RFC 3339 timestamp in UTC.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+    /// eprintln!("{}", error.display_timestamps());
+    /// // Failed to sync (2024-05-01T12:30:00.124Z)
+    /// //   caused by: Connection refused (2024-05-01T12:30:00.123Z)
+    /// ```
+    #[cfg(feature = "timestamp")]
+    pub fn display_timestamps(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { timestamps: true, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain like the [Display] implementation, but write the thread, that created the innermost frame,
+    /// behind it like `(on thread "worker-3")`.
+    /// Threads without a name are written with their [ThreadId](std::thread::ThreadId).
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let thread = std::thread::Builder::new().name("worker-3".to_string());
+    /// let rendered = thread.spawn(|| {
+    ///     let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+    ///     error.display_thread().to_string()
+    /// });
+    /// assert_eq!(rendered.unwrap().join().unwrap(), "\
+    /// Failed to sync
+    ///   caused by: Connection refused (on thread \"worker-3\")");
+    /// ```
+    #[cfg(feature = "thread_info")]
+    pub fn display_thread(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { threads: true, ..Layout::DEFAULT } }
+    }
+
+    /// Write the chain followed by a newline into an [io::Write] target, like [stderr](io::stderr) or a [File](std::fs::File).
+    ///
+    /// The chain is rendered in memory first and handed to the writer at once,
+    /// so unbuffered targets don't see a separate write for every frame.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("I/O Error").context("Failed to read file");
+    /// error.write_to(&mut std::io::stderr().lock()).unwrap();
+    /// # let mut buffer = Vec::new();
+    /// # error.write_to(&mut buffer).unwrap();
+    /// # assert_eq!(buffer, b"Failed to read file\n  caused by: I/O Error\n");
+    /// ```
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut rendered = self.to_string();
+        rendered.push('\n');
+        w.write_all(rendered.as_bytes())?;
+        w.flush()
+    }
+
+    /// Render the chain with more or less detail, for example depending on `-q`/`-v` command line flags.
+    ///
+    /// | Level | Output                                                                            |
+    /// |-------|-----------------------------------------------------------------------------------|
+    /// | 0     | Only the outermost message                                                        |
+    /// | 1     | The first 3 frames of the chain like `{:.3}`, with how many causes were left out  |
+    /// | 2+    | The whole chain and all metadata, that was recorded (like [Locations](crate::ErrorMessage::location), timestamps, threads, backtraces and span traces) |
+    ///
+    /// [Report::with_verbosity](crate::Report::with_verbosity) prints the chain this way, when `main` returns.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_err_context("Failed to read file")
+    ///     .with_err_context("Failed to load configuration")
+    ///     .with_err_context("Failed to start the program");
+    /// let error = result.unwrap_err();
+    /// assert_eq!(error.display_verbosity(0).to_string(), "Failed to start the program");
+    /// assert_eq!(error.display_verbosity(1).to_string(), "\
+    /// Failed to start the program
+    ///   caused by: Failed to load configuration
+    ///   caused by: Failed to read file
+    ///   ... 1 more cause");
+    /// ```
+    pub fn display_verbosity(&self, level: u8) -> impl Display + '_ {
+        Verbosity { error: self, level }
+    }
+
+    /// Render the chain in reverse, starting with the root cause, like a Python traceback.
+    /// Details, branches and hints are written below their frames just like in the [Display] implementation.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+    ///     .with_err_context("Failed to load configuration")
+    ///     .with_err_context("Failed to start the program");
+    /// assert_eq!(result.unwrap_err().display_root_first().to_string(), "\
+    /// Kind(UnexpectedEof)
+    ///   which caused: Failed to load configuration
+    ///   which caused: Failed to start the program");
+    /// ```
+    pub fn display_root_first(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { style: Style::RootFirst, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain with the index of every frame in front of it, so frames can be referenced in bug reports.
+    /// The foreign error at the end of the chain is numbered as well.
+    /// Details, branches and hints are written below their frames just like in the [Display] implementation.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+    ///     .with_err_context("Failed to load configuration")
+    ///     .with_err_context("Failed to start the program");
+    /// assert_eq!(result.unwrap_err().display_numbered().to_string(), "\
+    /// 0: Failed to start the program
+    /// 1: caused by: Failed to load configuration
+    /// 2: caused by: Kind(UnexpectedEof)");
+    /// ```
+    pub fn display_numbered(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { style: Style::Numbered, ..Layout::DEFAULT } }
+    }
+
+    /// Render the chain as a tree with box-drawing connectors, where every cause is indented one level deeper.
+    /// Continuation lines of multi-line messages are aligned under the text of their frame
+    /// and details, branches and hints are written below their frames just like in the [Display] implementation.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+    ///     .with_err_context("Failed to load configuration")
+    ///     .with_err_context("Failed to start the program");
+    /// assert_eq!(result.unwrap_err().display_tree().to_string(), "\
+    /// Failed to start the program
+    /// ╰─▶ Failed to load configuration
+    ///     ╰─▶ Kind(UnexpectedEof)");
+    /// ```
+    pub fn display_tree(&self) -> impl Display + '_ {
+        WithLayout { error: self, layout: Layout { style: Style::Tree, ..Layout::DEFAULT } }
+    }
+
+    /// Render the whole chain on a single line, joining the frames with `": "`.
+    /// Line breaks inside of messages are replaced with spaces, so the output never spans multiple lines.
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+    ///     .with_err_context("Failed to load configuration")
+    ///     .with_err_context("Failed to start the program");
+    /// assert_eq!(
+    ///     result.unwrap_err().to_compact_string(),
+    ///     "Failed to start the program: Failed to load configuration: Kind(UnexpectedEof)"
+    /// );
+    /// ```
+    pub fn to_compact_string(&self) -> String {
+        self.to_compact_string_with(": ")
+    }
+
+    /// Render the whole chain on a single line, joining the frames with the given separator.
+    /// See [to_compact_string](ErrorMessage::to_compact_string).
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("I/O Error").context("Failed to read file");
+    /// assert_eq!(error.to_compact_string_with(" -> "), "Failed to read file -> I/O Error");
+    /// ```
+    pub fn to_compact_string_with(&self, separator: &str) -> String {
+        let mut compact = String::new();
+        self.write_compact(separator, &mut compact).expect("Writing into a String can't fail");
+        compact
+    }
+
+    /// Writes the chain on a single line, see [to_compact_string_with](ErrorMessage::to_compact_string_with)
+    pub(crate) fn write_compact<W: fmt::Write>(&self, separator: &str, w: &mut W) -> fmt::Result {
+        for (index, frame) in self.frames().enumerate() {
+            if index > 0 {
+                w.write_str(separator)?;
+            }
+            let frame = frame.to_string();
+            let mut lines = frame.lines();
+            w.write_str(lines.next().unwrap_or_default())?;
+            for line in lines {
+                w.write_char(' ')?;
+                w.write_str(line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Verbosity<'a> {
+    error: &'a ErrorMessage,
+    level: u8,
+}
+
+impl Display for Verbosity<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.level {
+            0 => return write!(f, "{}", Frame::Message(self.error)),
+            1 => return write_layout(self.error, DEFAULT_DEPTH, Layout::DEFAULT, f),
+            _ => write_layout(self.error, usize::MAX, Layout::VERBOSE, f)?,
+        }
+        #[cfg(feature = "backtrace")]
+        if let Some(backtrace) = self.error.backtrace() {
+            write!(f, "\n\nstack backtrace:\n{}", backtrace.to_string().trim_end())?;
+        }
+        #[cfg(feature = "spantrace")]
+        if let Some(span_trace) = self.error.span_trace() {
+            write!(f, "\n\nspan trace:\n{}", span_trace.to_string().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// How [write_layout] writes the frames of a chain
+#[derive(Clone, Copy)]
+struct Layout<'a> {
+    /// How the frames are arranged
+    style: Style,
+    /// Written in front of every cause
+    label: &'a str,
+    /// How the foreign error at the end of the chain is written
+    leaf: Leaf,
+    /// Whether the type name of the foreign error is written in front of it
+    type_names: bool,
+    /// The column at which messages are wrapped
+    width: usize,
+    /// Whether control characters are replaced with visible escapes
+    sanitize: bool,
+    /// Whether the location, where every frame was created, is written behind its message
+    #[cfg(any(feature = "macros", feature = "location"))]
+    locations: bool,
+    /// Whether the creation time is written behind every message
+    #[cfg(feature = "timestamp")]
+    timestamps: bool,
+    /// Whether the thread, that created the innermost frame, is written behind it
+    #[cfg(feature = "thread_info")]
+    threads: bool,
+}
+
+/// The ways to arrange the frames of a chain
+#[derive(Clone, Copy)]
+enum Style {
+    /// Starting with the outermost frame and the label in front of every cause
+    Plain,
+    /// Starting with the root cause and `which caused` in front of every frame it caused
+    RootFirst,
+    /// Like [Style::Plain], but with the index of every frame in front of it
+    Numbered,
+    /// Box-drawing connectors in front of every cause, which is indented one level deeper
+    Tree,
+}
+
+/// The ways to write the foreign error at the end of a chain
+#[derive(Clone, Copy)]
+enum Leaf {
+    Debug,
+    Display,
+    /// The Display output and the Debug output in brackets, if they differ
+    Both,
+}
+
+impl Layout<'_> {
+    /// The layout of the [Display] implementation
+    const DEFAULT: Layout<'static> = Layout {
+        style: Style::Plain,
+        label: "caused by",
+        leaf: Leaf::Debug,
+        type_names: false,
+        width: usize::MAX,
+        sanitize: false,
+        #[cfg(any(feature = "macros", feature = "location"))]
+        locations: false,
+        #[cfg(feature = "timestamp")]
+        timestamps: false,
+        #[cfg(feature = "thread_info")]
+        threads: false,
+    };
+
+    /// The layout of [ErrorMessage::display_verbosity] at level 2, which writes all metadata behind the messages
+    const VERBOSE: Layout<'static> = Layout {
+        #[cfg(any(feature = "macros", feature = "location"))]
+        locations: true,
+        #[cfg(feature = "timestamp")]
+        timestamps: true,
+        #[cfg(feature = "thread_info")]
+        threads: true,
+        ..Layout::DEFAULT
+    };
+
+    fn write_frame<W: fmt::Write>(&self, frame: Frame, w: &mut W) -> fmt::Result {
+        match (frame, self.leaf) {
+            (Frame::Cause(cause), Leaf::Display) => write!(w, "{cause}"),
+            (Frame::Cause(cause), Leaf::Both) => {
+                let (display, debug) = (cause.to_string(), format!("{cause:?}"));
+                if display == debug { w.write_str(&display) } else { write!(w, "{display} [{debug}]") }
+            }
+            (frame, _) => write!(w, "{frame}"),
+        }
+    }
+
+    /// Writes the label in front of a cause and the name of its type, if it is known and wanted
+    fn write_label<W: fmt::Write>(&self, type_name: Option<&str>, w: &mut W) -> fmt::Result {
+        match (self.label.is_empty(), type_name.filter(|_| self.type_names)) {
+            (true, None) => Ok(()),
+            (true, Some(type_name)) => write!(w, "[{type_name}] "),
+            (false, None) => write!(w, "{}: ", self.label),
+            (false, Some(type_name)) => write!(w, "{} [{type_name}]: ", self.label),
+        }
+    }
+}
+
+struct WithLayout<'a> {
+    error: &'a ErrorMessage,
+    layout: Layout<'a>,
+}
+
+impl Display for WithLayout<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.layout.sanitize {
+            write_layout(self.error, usize::MAX, self.layout, &mut Sanitized { w: f })
+        } else {
+            write_layout(self.error, usize::MAX, self.layout, f)
+        }
+    }
+}
+
+/// Replaces all control characters but line breaks with visible escapes
+struct Sanitized<'a, W: fmt::Write> {
+    w: &'a mut W,
+}
+
+impl<W: fmt::Write> fmt::Write for Sanitized<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '\n' => self.w.write_char(c)?,
+                c if c.is_ascii_control() => write!(self.w, "\\x{:02x}", c as u32)?,
+                c if c.is_control() => write!(self.w, "\\u{{{:x}}}", c as u32)?,
+                c => self.w.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the first `depth` frames of the chain with one frame per line and how many causes were left out
+fn write_layout<W: fmt::Write>(error: &ErrorMessage, depth: usize, layout: Layout, w: &mut W) -> fmt::Result {
+    match layout.style {
+        Style::RootFirst => {
+            let frames: Vec<Frame> = error.frames().collect();
+            write_frames(error, frames.into_iter().rev(), depth, layout, w)
+        }
+        Style::Plain | Style::Numbered | Style::Tree => write_frames(error, error.frames(), depth, layout, w),
+    }
+}
+
+/// Writes the given frames of the chain in the order of the iterator, see [write_layout]
+fn write_frames<'a, W: fmt::Write>(error: &ErrorMessage, frames: impl Iterator<Item = Frame<'a>>, depth: usize, layout: Layout, w: &mut W) -> fmt::Result {
+    let depth = depth.max(1);
+    let mut omitted = 0;
+    // The type of the foreign error is recorded in the innermost ErrorMessage
+    let mut cause_type = None;
+    if layout.type_names {
+        cause_type = error.frames().filter_map(Frame::message).last().and_then(|error| error.cause_type);
+    }
+    let mut number_width = 0;
+    if let Style::Numbered = layout.style {
+        number_width = (error.depth() - 1).to_string().len();
+    }
+    for (index, frame) in frames.enumerate() {
+        if index >= depth {
+            omitted += 1;
+            continue;
+        }
+        if index > 0 {
+            w.write_char('\n')?;
+        }
+        let cause_type = cause_type.filter(|_| matches!(frame, Frame::Cause(_)));
+        let mut prefix = Counted { w: &mut *w, chars: 0 };
+        match layout.style {
+            Style::Plain if index > 0 => {
+                prefix.write_str("  ")?;
+                layout.write_label(cause_type, &mut prefix)?;
+            }
+            Style::RootFirst if index > 0 => prefix.write_str("  which caused: ")?,
+            Style::Numbered => {
+                write!(prefix, "{index:>number_width$}: ")?;
+                if index > 0 {
+                    layout.write_label(cause_type, &mut prefix)?;
+                }
+            }
+            Style::Tree if index > 0 => write!(prefix, "{:indent$}╰─▶ ", "", indent = (index - 1) * 4)?,
+            Style::Plain | Style::RootFirst | Style::Tree => {}
+        }
+        let gutter = prefix.chars;
+        if layout.width == usize::MAX {
+            layout.write_frame(frame, &mut Aligned { w: &mut *w, indent: gutter })?;
+        } else {
+            let mut text = String::new();
+            layout.write_frame(frame, &mut text)?;
+            write_wrapped(&text, layout.width.saturating_sub(gutter).max(1), gutter, w)?;
+        }
+        #[cfg(any(feature = "macros", feature = "location"))]
+        if let Some(location) = frame.message().and_then(ErrorMessage::location).filter(|_| layout.locations) {
+            write!(w, " (at {location})")?;
+        }
+        #[cfg(feature = "timestamp")]
+        if let Some(error) = frame.message().filter(|_| layout.timestamps) {
+            w.write_str(" (")?;
+            crate::timestamp::write_rfc3339(error.created_at(), w)?;
+            w.write_char(')')?;
+        }
+        #[cfg(feature = "thread_info")]
+        if let Some(thread) = frame.message().and_then(|error| error.metadata.as_ref()?.thread.as_ref()).filter(|_| layout.threads) {
+            match &thread.name {
+                Some(name) => write!(w, " (on thread {name:?})")?,
+                None => write!(w, " (on thread {:?})", thread.id)?,
+            }
+        }
+        if let Some(error) = frame.message() {
+            let indent = match layout.style {
+                Style::Numbered => number_width + 4,
+                Style::Tree => index * 4 + 2,
+                Style::Plain | Style::RootFirst if index == 0 => 2,
+                Style::Plain | Style::RootFirst => 4,
+            };
+            write_below(error, indent, layout, w)?;
+        }
+    }
+    if omitted > 0 {
+        write!(w, "\n  {}", more_causes(omitted))?;
+    }
+    write_hints(error, w)
+}
+
+/// Writes the [hints](ErrorMessage::hints) of the whole chain below it
+fn write_hints<W: fmt::Write>(error: &ErrorMessage, w: &mut W) -> fmt::Result {
+    for hint in error.hints() {
+        w.write_str("\n  hint: ")?;
+        Aligned { w: &mut *w, indent: "  hint: ".len() }.write_str(hint)?;
+    }
+    Ok(())
+}
+
+/// Indents every line break by `indent` spaces,
+/// so the lines of multi-line messages stay aligned under the start of their frame
+pub(crate) struct Aligned<'a, W: fmt::Write> {
+    pub(crate) w: &'a mut W,
+    pub(crate) indent: usize,
+}
+
+impl<W: fmt::Write> fmt::Write for Aligned<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (index, line) in s.split('\n').enumerate() {
+            if index > 0 {
+                self.w.write_char('\n')?;
+                for _ in 0..self.indent {
+                    self.w.write_char(' ')?;
+                }
+            }
+            self.w.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the characters, that are written through it, so the prefix in front of a frame can be measured without allocating
+struct Counted<'a, W: fmt::Write> {
+    w: &'a mut W,
+    chars: usize,
+}
+
+impl<W: fmt::Write> fmt::Write for Counted<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.chars += s.chars().count();
+        self.w.write_str(s)
+    }
+}
+
+/// Writes the text with at most `width` characters per line, breaking lines between words.
+/// Words, that are longer than a line, are broken up.
+/// Every line but the first is indented by `indent` spaces.
+fn write_wrapped<W: fmt::Write>(text: &str, width: usize, indent: usize, w: &mut W) -> fmt::Result {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut length = 0;
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            if length > 0 && length + 1 + word.len() <= width {
+                line.push(' ');
+                line.extend(&word);
+                length += 1 + word.len();
+                continue;
+            }
+            if length > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            while word.len() > width {
+                lines.push(word.drain(..width).collect());
+            }
+            length = word.len();
+            line = word.into_iter().collect();
+        }
+        lines.push(line);
+    }
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            write!(w, "\n{:indent$}", "")?;
+        }
+        w.write_str(line)?;
+    }
+    Ok(())
+}
+
+/// Writes the lines, that belong below the message of a frame and are indented by `indent` spaces:
+/// Its [details](ErrorMessage::details) and its [branches](ErrorMessage::branches) as a tree.
+/// Each branch is rendered with its own chain, which is indented beneath the branch and always starts with its outermost frame.
+fn write_below<W: fmt::Write>(error: &ErrorMessage, indent: usize, layout: Layout, w: &mut W) -> fmt::Result {
+    for (key, value) in error.details() {
+        write!(w, "\n{:indent$}{key}: {value}", "")?;
+    }
+    let branches = error.branches();
+    for (branch_index, branch) in branches.iter().enumerate() {
+        let (first, rest) = if branch_index + 1 == branches.len() { ("└─", "   ") } else { ("├─", "│  ") };
+        let mut rendered = String::new();
+        write_layout(branch, usize::MAX, Layout { style: Style::Plain, ..layout }, &mut rendered)?;
+        let mut lines = rendered.lines();
+        write!(w, "\n{:indent$}{first} ", "")?;
+        layout.write_label(None, w)?;
+        w.write_str(lines.next().unwrap_or_default())?;
+        for line in lines {
+            write!(w, "\n{:indent$}{rest}{line}", "")?;
+        }
+    }
+    Ok(())
+}
+
+/// The placeholder for frames, that were left out
+pub(crate) fn more_causes(count: usize) -> String {
+    format!("... {count} more {}", if count == 1 { "cause" } else { "causes" })
+}
@@ -0,0 +1,50 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+use std::fmt::Display;
+
+/// [WithDisplayContext] is implemented for every [Result], whose error implements [Display],
+/// for errors, that don't implement [Error](std::error::Error), like the errors of some FFI wrappers.
+///
+/// It is a separate trait with its own method names, because an implementation of [WithContext](crate::WithContext)
+/// for all [Display] errors would conflict with the one for all [Errors](std::error::Error), which implement [Display] as well.
+/// The Display output of the error becomes the message of the innermost frame.
+pub trait WithDisplayContext<T> {
+    /// Like [with_err_context](crate::WithContext::with_err_context), but for errors, that only implement [Display].
+    ///
+    /// ```rust
+    /// # use std::fmt;
+    /// use errors_with_context::prelude::*;
+    /// struct DeviceError(i32);
+    /// impl fmt::Display for DeviceError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         write!(f, "Device returned status {}", self.0)
+    ///     }
+    /// }
+    /// fn open_device() -> Result<(), DeviceError> { Err(DeviceError(-5)) }
+    ///
+    /// let error = open_device().with_display_err_context("Failed to open the camera").unwrap_err();
+    /// assert_eq!(error.to_string(), "Failed to open the camera\n  caused by: Device returned status -5");
+    /// ```
+    fn with_display_err_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+
+    /// Like [with_dyn_err_context](crate::WithContext::with_dyn_err_context), but for errors, that only implement [Display].
+    fn with_dyn_display_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage>;
+}
+
+impl<T, E: Display> WithDisplayContext<T> for Result<T, E> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_display_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(ErrorMessage::new(error).context(context)),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_display_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(ErrorMessage::new(error).context_static(context())),
+        }
+    }
+}
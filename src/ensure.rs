@@ -0,0 +1,93 @@
+use crate::ErrorMessage;
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+/// Return an `Err(ErrorMessage)` with the provided context string, if the condition is false.
+///
+/// ```rust
+/// use errors_with_context::{ensure, ErrorMessage};
+/// # fn func() -> Result<(), ErrorMessage> {
+/// let retries = 5;
+/// ensure(retries < 3, "Too many retries")?;
+/// #   Ok(())
+/// # }
+/// # assert_eq!(func().unwrap_err().to_string(), "Too many retries");
+/// ```
+#[cfg_attr(feature = "location", track_caller)]
+pub fn ensure(condition: bool, context: impl ToString) -> Result<(), ErrorMessage> {
+    if condition { Ok(()) } else { ErrorMessage::err(context) }
+}
+
+/// Return an `Err(ErrorMessage)` with the computed context string, if the condition is false.
+///
+/// ```rust
+/// use errors_with_context::{ensure_dyn, ErrorMessage};
+/// # fn func() -> Result<(), ErrorMessage> {
+/// let retries = 5;
+/// ensure_dyn(retries < 3, || format!("Too many retries: {retries}"))?;
+/// #   Ok(())
+/// # }
+/// # assert_eq!(func().unwrap_err().to_string(), "Too many retries: 5");
+/// ```
+#[cfg_attr(feature = "location", track_caller)]
+pub fn ensure_dyn<S: Into<Cow<'static, str>>>(condition: bool, context: impl FnOnce() -> S) -> Result<(), ErrorMessage> {
+    if condition { Ok(()) } else { Err(ErrorMessage::new_static(context())) }
+}
+
+/// Return an `Err(ErrorMessage)`, if both values are not equal.
+/// Like [assert_eq], the message includes both values.
+///
+/// ```rust
+/// use errors_with_context::{ensure_eq, ErrorMessage};
+/// # fn func() -> Result<(), ErrorMessage> {
+/// let version = 2;
+/// ensure_eq(version, 1, "Unsupported version")?;
+/// #   Ok(())
+/// # }
+/// # assert_eq!(func().unwrap_err().to_string(), "Unsupported version\n  left: 2\n right: 1");
+/// ```
+/// prints
+/// ```text
+/// Unsupported version
+///   left: 2
+///  right: 1
+/// ```
+#[cfg_attr(feature = "location", track_caller)]
+pub fn ensure_eq<T: PartialEq<U> + Debug, U: Debug>(left: T, right: U, context: impl ToString) -> Result<(), ErrorMessage> {
+    ensure_dyn_eq(left, right, || context.to_string())
+}
+
+/// Return an `Err(ErrorMessage)` with the computed context string, if both values are not equal.
+/// See [ensure_eq].
+#[cfg_attr(feature = "location", track_caller)]
+pub fn ensure_dyn_eq<T: PartialEq<U> + Debug, U: Debug, S: Into<Cow<'static, str>>>(left: T, right: U, context: impl FnOnce() -> S) -> Result<(), ErrorMessage> {
+    if left == right { Ok(()) } else { Err(ErrorMessage::new_static(comparison(&context().into(), &left, &right))) }
+}
+
+/// Return an `Err(ErrorMessage)`, if both values are equal.
+/// Like [assert_ne], the message includes both values.
+///
+/// ```rust
+/// use errors_with_context::{ensure_ne, ErrorMessage};
+/// # fn func() -> Result<(), ErrorMessage> {
+/// let name = "";
+/// ensure_ne(name, "", "Name must not be empty")?;
+/// #   Ok(())
+/// # }
+/// # assert_eq!(func().unwrap_err().to_string(), "Name must not be empty\n  left: \"\"\n right: \"\"");
+/// ```
+#[cfg_attr(feature = "location", track_caller)]
+pub fn ensure_ne<T: PartialEq<U> + Debug, U: Debug>(left: T, right: U, context: impl ToString) -> Result<(), ErrorMessage> {
+    ensure_dyn_ne(left, right, || context.to_string())
+}
+
+/// Return an `Err(ErrorMessage)` with the computed context string, if both values are equal.
+/// See [ensure_ne].
+#[cfg_attr(feature = "location", track_caller)]
+pub fn ensure_dyn_ne<T: PartialEq<U> + Debug, U: Debug, S: Into<Cow<'static, str>>>(left: T, right: U, context: impl FnOnce() -> S) -> Result<(), ErrorMessage> {
+    if left != right { Ok(()) } else { Err(ErrorMessage::new_static(comparison(&context().into(), &left, &right))) }
+}
+
+fn comparison(context: &str, left: &dyn Debug, right: &dyn Debug) -> String {
+    format!("{context}\n  left: {left:?}\n right: {right:?}")
+}
@@ -1,6 +1,13 @@
+use std::any::Any;
+#[cfg(feature = "backtrace")]
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::borrow::Cow;
 use std::error::Error;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
+use crate::display::more_causes;
+#[cfg(any(feature = "macros", feature = "location"))]
+use crate::Location;
 
 /// To get an [ErrorMessage] without an underlying [Error](std::error::Error).
 /// ```rust
@@ -26,13 +33,113 @@ use std::fmt::{Debug, Display, Formatter};
 /// ErrorMessage::with_context("Error description", io::Error::last_os_error());
 /// ```
 pub struct ErrorMessage {
-    pub(crate) message: String,
-    #[cfg(feature = "send")]
-    pub(crate) cause: Option<Box<dyn Error + Send>>,
-    #[cfg(not(feature = "send"))]
-    pub(crate) cause: Option<Box<dyn Error>>,
+    pub(crate) message: Cow<'static, str>,
+    pub(crate) cause: Option<Cause>,
+    /// The [type name](std::any::type_name) of the cause, recorded when it was attached
+    pub(crate) cause_type: Option<&'static str>,
+    pub(crate) metadata: Option<Box<Metadata>>,
+}
+
+/// Everything a frame can record besides its message and cause.
+///
+/// It is only allocated, once something was recorded, so an [ErrorMessage] stays small enough to be returned in a [Result].
+#[derive(Default)]
+pub(crate) struct Metadata {
+    pub(crate) id: Option<Cow<'static, str>>,
+    pub(crate) code: Option<String>,
+    pub(crate) branches: Vec<ErrorMessage>,
+    pub(crate) details: Vec<(String, String)>,
+    pub(crate) attachments: Vec<Box<dyn Any + Send + Sync>>,
+    pub(crate) hints: Vec<String>,
+    pub(crate) transient: bool,
+    pub(crate) exit_code: Option<u8>,
+    #[cfg(feature = "http")]
+    pub(crate) status: Option<http::StatusCode>,
+    #[cfg(any(feature = "macros", feature = "location"))]
+    pub(crate) location: Option<Location>,
+    #[cfg(feature = "backtrace")]
+    pub(crate) backtrace: Option<Backtrace>,
+    #[cfg(feature = "timestamp")]
+    pub(crate) timestamp: Option<std::time::SystemTime>,
+    #[cfg(feature = "thread_info")]
+    pub(crate) thread: Option<ThreadInfo>,
+    #[cfg(feature = "spantrace")]
+    pub(crate) span_trace: Option<tracing_error::SpanTrace>,
+}
+
+/// The thread, that created the innermost frame of a chain
+#[cfg(feature = "thread_info")]
+pub(crate) struct ThreadInfo {
+    pub(crate) name: Option<String>,
+    pub(crate) id: std::thread::ThreadId,
+}
+
+/// The box, that holds the cause of an [ErrorMessage]
+#[cfg(feature = "send")]
+pub(crate) type BoxedError = Box<dyn Error + Send>;
+/// The box, that holds the cause of an [ErrorMessage]
+#[cfg(not(feature = "send"))]
+pub(crate) type BoxedError = Box<dyn Error>;
+
+/// The cause of a frame.
+///
+/// Nested [ErrorMessages](ErrorMessage) are kept apart from foreign errors, so walking the chain
+/// for formatting and serializing doesn't need to downcast every frame.
+pub(crate) enum Cause {
+    Message(Box<ErrorMessage>),
+    Other(BoxedError),
+}
+
+impl Cause {
+    /// Sorts a boxed error into the right variant, which is the only place, where a nested [ErrorMessage] is downcast
+    pub(crate) fn from_boxed(cause: BoxedError) -> Cause {
+        match cause.downcast::<ErrorMessage>() {
+            Ok(error) => Cause::Message(error),
+            Err(cause) => Cause::Other(cause),
+        }
+    }
+
+    pub(crate) fn into_boxed(self) -> BoxedError {
+        match self {
+            Cause::Message(error) => error,
+            Cause::Other(cause) => cause,
+        }
+    }
+
+    pub(crate) fn frame(&self) -> Frame<'_> {
+        match self {
+            Cause::Message(error) => Frame::Message(error),
+            Cause::Other(cause) => Frame::Cause(&**cause),
+        }
+    }
+}
+
+impl Debug for Cause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Cause::Message(error) => Debug::fmt(error, f),
+            Cause::Other(cause) => Debug::fmt(cause, f),
+        }
+    }
+}
+
+/// Stands in for an error, that can't be moved into the chain, and writes its Debug output in both Display and Debug
+struct DebugText(String);
+
+impl Debug for DebugText {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Display for DebugText {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
+impl Error for DebugText {}
+
 impl ErrorMessage {
     /// To get an [ErrorMessage] without an underlying [Error](std::error::Error) as a cause.
     /// 
@@ -42,8 +149,25 @@ impl ErrorMessage {
     /// ErrorMessage::new("Error description");
     /// // prints "Error description" without listing a cause
     /// ```
+    #[cfg_attr(feature = "location", track_caller)]
     pub fn new(message: impl ToString) -> ErrorMessage {
-        ErrorMessage { message: message.to_string(), cause: None }
+        ErrorMessage::new_static(message.to_string())
+    }
+
+    /// Like [new](ErrorMessage::new), but a `&'static str` is stored as it is, instead of copying it into a new [String].
+    /// Owned [Strings](String) are moved into the [ErrorMessage] without a copy as well.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new_static("Error description");
+    /// assert_eq!(error.message(), "Error description");
+    /// ```
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn new_static(message: impl Into<Cow<'static, str>>) -> ErrorMessage {
+        let mut error = ErrorMessage::frame(message.into());
+        error.created();
+        error
     }
     /// This function creates a [Result<T, ErrorMessage>], so you can immediately throw it with `?`.
     /// 
@@ -55,9 +179,59 @@ impl ErrorMessage {
     /// // [...]
     /// }
     /// ```
+    #[cfg_attr(feature = "location", track_caller)]
     pub fn err<T>(message: impl ToString) -> Result<T, ErrorMessage> {
-        Err(ErrorMessage { message: message.to_string(), cause: None })
+        Err(ErrorMessage::new(message))
+    }
+    /// Lift any value, that implements [Display], into an [ErrorMessage] without a cause.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::path::PathBuf;
+    /// use errors_with_context::ErrorMessage;
+    /// let path = PathBuf::from("/etc/app.toml");
+    /// let error = ErrorMessage::from_display(path.display());
+    /// assert_eq!(error.to_string(), "/etc/app.toml");
+    /// ```
+    ///
+    /// ```rust
+    /// # use std::fmt;
+    /// use errors_with_context::ErrorMessage;
+    /// enum Status { Offline }
+    ///
+    /// impl fmt::Display for Status {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         f.write_str("Device is offline")
+    ///     }
+    /// }
+    ///
+    /// let error = ErrorMessage::from_display(Status::Offline);
+    /// assert_eq!(error.to_string(), "Device is offline");
+    /// ```
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn from_display(value: impl Display) -> ErrorMessage {
+        ErrorMessage::new(value)
+    }
+
+    /// Lift any value, that implements [Display], into a `Result<T, ErrorMessage>`, so you can immediately throw it with `?`.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::path::Path;
+    /// use errors_with_context::ErrorMessage;
+    /// fn check(path: &Path) -> Result<(), ErrorMessage> {
+    ///     if !path.exists() {
+    ///         ErrorMessage::err_from_display(path.display())?
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # assert_eq!(check(Path::new("missing.file")).unwrap_err().to_string(), "missing.file");
+    /// ```
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn err_from_display<T>(value: impl Display) -> Result<T, ErrorMessage> {
+        ErrorMessage::err(value)
     }
+
     /// This function allows one to manually wrap an [Error](std::error::Error).
     /// 
     /// Example:
@@ -67,8 +241,9 @@ impl ErrorMessage {
     /// ErrorMessage::with_context("Error description", io::Error::last_os_error());
     /// ```
     #[cfg(not(feature = "send"))]
+    #[cfg_attr(feature = "location", track_caller)]
     pub fn with_context<E: Error + 'static>(message: impl ToString, cause: E) -> ErrorMessage {
-        ErrorMessage { message: message.to_string(), cause: Some(Box::new(cause)) }
+        ErrorMessage::with_boxed_context(message.to_string().into(), Box::new(cause), Some(std::any::type_name::<E>()))
     }
 
     /// This function allows one to manually wrap an [Error](std::error::Error).
@@ -80,76 +255,1292 @@ impl ErrorMessage {
     /// ErrorMessage::with_context("Error description", io::Error::last_os_error());
     /// ```
     #[cfg(feature = "send")]
+    #[cfg_attr(feature = "location", track_caller)]
     pub fn with_context<E: Error + Send + 'static>(message: impl ToString, cause: E) -> ErrorMessage {
-        ErrorMessage { message: message.to_string(), cause: Some(Box::new(cause)) }
+        ErrorMessage::with_boxed_context(message.to_string().into(), Box::new(cause), Some(std::any::type_name::<E>()))
     }
-}
 
-impl Error for ErrorMessage {}
+    /// Turn any [Error](std::error::Error) into an [ErrorMessage] without inventing a context.
+    /// Its Display output becomes the message and the error itself becomes the cause, so its Debug output is still shown.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::from_error(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(error.message(), "entity not found");
+    /// assert_eq!(error.to_string(), "entity not found\n  caused by: Kind(NotFound)");
+    /// ```
+    #[cfg(not(feature = "send"))]
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn from_error<E: Error + 'static>(error: E) -> ErrorMessage {
+        ErrorMessage::with_context(error.to_string(), error)
+    }
 
-impl Display for ErrorMessage {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.message)?;
-        if let Some(cause) = &self.cause {
-            fmt_cause(cause, f)?;
-        }
-        Ok(())
+    /// Turn any [Error](std::error::Error) into an [ErrorMessage] without inventing a context.
+    /// Its Display output becomes the message and the error itself becomes the cause, so its Debug output is still shown.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::from_error(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(error.message(), "entity not found");
+    /// assert_eq!(error.to_string(), "entity not found\n  caused by: Kind(NotFound)");
+    /// ```
+    #[cfg(feature = "send")]
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn from_error<E: Error + Send + 'static>(error: E) -> ErrorMessage {
+        ErrorMessage::with_context(error.to_string(), error)
     }
-}
 
-#[cfg(not(feature = "pretty_debug_errors"))]
-impl Debug for ErrorMessage {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let mut s = f.debug_struct("ErrorMessage");
-        s.field("message", &self.message);
+    /// Wrap this [ErrorMessage] in a new frame with the given message.
+    /// This is the same as calling [with_err_context](crate::WithContext::with_err_context) on an `Err(self)`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("I/O Error")
+    ///     .context("Failed to read file");
+    /// assert_eq!(error.to_string(), "Failed to read file\n  caused by: I/O Error");
+    /// ```
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn context(self, message: impl ToString) -> ErrorMessage {
+        ErrorMessage::with_context(message, self)
+    }
+
+    /// Like [context](ErrorMessage::context), but a `&'static str` is stored as it is, instead of copying it into a new [String].
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new_static("I/O Error")
+    ///     .context_static("Failed to read file");
+    /// assert_eq!(error.to_string(), "Failed to read file\n  caused by: I/O Error");
+    /// ```
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn context_static(self, message: impl Into<Cow<'static, str>>) -> ErrorMessage {
+        ErrorMessage::with_boxed_context(message.into(), Box::new(self), Some(std::any::type_name::<ErrorMessage>()))
+    }
 
-        if let Some(cause) = &self.cause {
-            if let Some(cause) = cause.downcast_ref::<ErrorMessage>() {
-                s.field("cause", cause);
-            } else {
-                s.field("cause", &Some(cause));
+    /// Wrap this [ErrorMessage] in a new frame with the message returned by the closure.
+    /// This is the same as calling [with_dyn_err_context](crate::WithContext::with_dyn_err_context) on an `Err(self)`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let path = "config.json";
+    /// let error = ErrorMessage::new("I/O Error")
+    ///     .dyn_context(|| format!("Failed to read file '{path}'"));
+    /// assert_eq!(error.to_string(), "Failed to read file 'config.json'\n  caused by: I/O Error");
+    /// ```
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn dyn_context<S: Into<Cow<'static, str>>>(self, message: impl FnOnce() -> S) -> ErrorMessage {
+        self.context_static(message())
+    }
+
+    /// Combine several independent errors under one message, for example all problems found while validating a file.
+    ///
+    /// With a single error, this is the same as [context](ErrorMessage::context) and without errors, the same as [new](ErrorMessage::new).
+    /// With more errors, every error becomes a branch, which is shown as a tree:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::from_errors("Invalid configuration", [
+    ///     ErrorMessage::new("Expected a string").context("Invalid value for 'name'"),
+    ///     ErrorMessage::new("Port out of range"),
+    /// ]);
+    /// assert_eq!(error.to_string(), "\
+    /// Invalid configuration
+    ///   ├─ caused by: Invalid value for 'name'
+    ///   │    caused by: Expected a string
+    ///   └─ caused by: Port out of range");
+    /// ```
+    ///
+    /// The linear chain ends at a frame with several causes:
+    /// It has no [source](Error::source) and is its own [root_cause](ErrorMessage::root_cause).
+    /// Use [branches](ErrorMessage::branches) to walk into the individual errors.
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn from_errors(message: impl ToString, errors: impl IntoIterator<Item = ErrorMessage>) -> ErrorMessage {
+        let mut errors: Vec<ErrorMessage> = errors.into_iter().collect();
+        match errors.len() {
+            0 => ErrorMessage::new(message),
+            1 => ErrorMessage::with_context(message, errors.remove(0)),
+            _ => {
+                let mut error = ErrorMessage::frame(message.to_string().into());
+                error.metadata_mut().branches = errors;
+                error.created();
+                error
             }
-        } else {
-            s.field("cause", &None::<ErrorMessage>);
         }
+    }
 
-        s.finish()
+    /// Returns the errors combined by [from_errors](ErrorMessage::from_errors).
+    /// Frames with at most one cause have no branches.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::from_errors("Invalid configuration", [ErrorMessage::new("Missing name"), ErrorMessage::new("Missing port")]);
+    /// assert_eq!(error.branches().len(), 2);
+    /// assert_eq!(error.branches()[1].message(), "Missing port");
+    /// assert!(ErrorMessage::new("Missing name").branches().is_empty());
+    /// ```
+    pub fn branches(&self) -> &[ErrorMessage] {
+        self.metadata.as_ref().map_or(&[], |metadata| &metadata.branches)
     }
-}
 
-#[cfg(feature = "pretty_debug_errors")]
-impl Debug for ErrorMessage {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        Display::fmt(self, f)
+    /// Attach an underlying error to the innermost [ErrorMessage] of the chain.
+    ///
+    /// If the chain already ends in a foreign error, that error is replaced.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let mut error = ErrorMessage::new("Failed to validate config");
+    /// // [...]
+    /// error.set_cause(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(error.to_string(), "Failed to validate config\n  caused by: Kind(NotFound)");
+    /// ```
+    #[cfg(not(feature = "send"))]
+    pub fn set_cause<E: Error + 'static>(&mut self, cause: E) {
+        let innermost = self.innermost_mut();
+        innermost.cause = Some(Cause::from_boxed(Box::new(cause)));
+        innermost.cause_type = Some(std::any::type_name::<E>());
     }
-}
 
-#[allow(clippy::borrowed_box)]
-#[cfg(feature = "send")]
-fn fmt_cause(error: &Box<dyn Error + Send>, f: &mut Formatter) -> fmt::Result {
-    f.write_str("\n  caused by: ")?;
-    if let Some(cause) = error.downcast_ref::<ErrorMessage>() {
-        f.write_str(&cause.message)?;
-        if let Some(cause) = &cause.cause {
-            fmt_cause(cause, f)?;
+    /// Attach an underlying error to the innermost [ErrorMessage] of the chain.
+    ///
+    /// If the chain already ends in a foreign error, that error is replaced.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let mut error = ErrorMessage::new("Failed to validate config");
+    /// // [...]
+    /// error.set_cause(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(error.to_string(), "Failed to validate config\n  caused by: Kind(NotFound)");
+    /// ```
+    #[cfg(feature = "send")]
+    pub fn set_cause<E: Error + Send + 'static>(&mut self, cause: E) {
+        let innermost = self.innermost_mut();
+        innermost.cause = Some(Cause::from_boxed(Box::new(cause)));
+        innermost.cause_type = Some(std::any::type_name::<E>());
+    }
+
+    /// Attach an underlying error to the innermost [ErrorMessage] of the chain, see [set_cause](ErrorMessage::set_cause).
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Failed to read file")
+    ///     .context("Failed to validate config")
+    ///     .with_cause(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(error.to_string(), "Failed to validate config\n  caused by: Failed to read file\n  caused by: Kind(NotFound)");
+    /// ```
+    #[cfg(not(feature = "send"))]
+    pub fn with_cause<E: Error + 'static>(mut self, cause: E) -> ErrorMessage {
+        self.set_cause(cause);
+        self
+    }
+
+    /// Attach an underlying error to the innermost [ErrorMessage] of the chain, see [set_cause](ErrorMessage::set_cause).
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Failed to read file")
+    ///     .context("Failed to validate config")
+    ///     .with_cause(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(error.to_string(), "Failed to validate config\n  caused by: Failed to read file\n  caused by: Kind(NotFound)");
+    /// ```
+    #[cfg(feature = "send")]
+    pub fn with_cause<E: Error + Send + 'static>(mut self, cause: E) -> ErrorMessage {
+        self.set_cause(cause);
+        self
+    }
+
+    /// Append another chain below this one, for example when cleaning up after an error failed as well.
+    ///
+    /// If this chain ends in a foreign error, it is turned into a frame with its Debug text,
+    /// exactly as it appeared in the Display output, and `deeper` is appended below it.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Failed to write file")
+    ///     .context("Failed to save document")
+    ///     .merge(ErrorMessage::new("Failed to delete temporary file").context("Failed to roll back"));
+    /// assert_eq!(error.to_string(), "Failed to save document
+    ///   caused by: Failed to write file
+    ///   caused by: Failed to roll back
+    ///   caused by: Failed to delete temporary file");
+    /// ```
+    pub fn merge(mut self, deeper: ErrorMessage) -> ErrorMessage {
+        let innermost = self.innermost_mut();
+        let deeper = match innermost.cause.take() {
+            Some(foreign) => {
+                let mut frame = ErrorMessage::frame(format!("{foreign:?}").into());
+                frame.cause = Some(Cause::Message(Box::new(deeper)));
+                frame
+            }
+            None => deeper,
+        };
+        innermost.cause = Some(Cause::Message(Box::new(deeper)));
+        self
+    }
+
+    /// Replace the message of the outermost frame. Its causes stay untouched.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let mut error = ErrorMessage::new("ENOENT").context("Failed to stat inode");
+    /// error.set_message("Failed to open the file");
+    /// assert_eq!(error.to_string(), "Failed to open the file\n  caused by: ENOENT");
+    /// ```
+    pub fn set_message(&mut self, message: impl ToString) {
+        self.message = message.to_string().into();
+    }
+
+    /// Apply a closure to the message of the outermost frame only, for example to translate it before showing it to users.
+    /// Its causes stay untouched.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("ENOENT")
+    ///     .context("Failed to stat inode")
+    ///     .map_message(|message| message.replace("stat inode", "open the file"));
+    /// assert_eq!(error.to_string(), "Failed to open the file\n  caused by: ENOENT");
+    /// ```
+    pub fn map_message(mut self, f: impl FnOnce(String) -> String) -> ErrorMessage {
+        self.message = f(std::mem::take(&mut self.message).into_owned()).into();
+        self
+    }
+
+    /// Apply a closure to the message of every frame, for example to redact secrets before logging.
+    ///
+    /// A foreign error at the end of the chain is turned into a frame with its transformed Debug text.
+    /// The order and the depth of the chain stay the same and [branches](ErrorMessage::branches) are transformed as well.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused")
+    ///     .context("Failed to connect to 'postgres://admin:hunter2@db'")
+    ///     .map_messages(|message| message.replace("hunter2", "****"));
+    /// assert_eq!(error.to_string(), "Failed to connect to 'postgres://admin:****@db'\n  caused by: Connection refused");
+    /// ```
+    pub fn map_messages(self, mut f: impl FnMut(String) -> String) -> ErrorMessage {
+        self.map_messages_dyn(&mut f)
+    }
+
+    /// [map_messages](ErrorMessage::map_messages) without generics, so it can recurse into [branches](ErrorMessage::branches)
+    fn map_messages_dyn(self, f: &mut dyn FnMut(String) -> String) -> ErrorMessage {
+        let (mut frames, leaf) = self.into_frames();
+        for frame in &mut frames {
+            frame.message = f(std::mem::take(&mut frame.message).into_owned()).into();
+            if let Some(metadata) = &mut frame.metadata {
+                metadata.branches = std::mem::take(&mut metadata.branches).into_iter().map(|branch| branch.map_messages_dyn(f)).collect();
+            }
+        }
+        if let Some(leaf) = leaf {
+            frames.push(ErrorMessage::frame(f(format!("{leaf:?}")).into()));
         }
-    } else {
-        Debug::fmt(error, f)?;
+        ErrorMessage::from_frames(frames, None)
     }
-    Ok(())
-}
 
-#[allow(clippy::borrowed_box)]
-#[cfg(not(feature = "send"))]
-fn fmt_cause(error: &Box<dyn Error>, f: &mut Formatter) -> fmt::Result {
-    f.write_str("\n  caused by: ")?;
-    if let Some(cause) = error.downcast_ref::<ErrorMessage>() {
-        f.write_str(&cause.message)?;
-        if let Some(cause) = &cause.cause {
-            fmt_cause(cause, f)?;
+    /// Limit the chain to `max_depth` frames.
+    /// The first `max_depth - 1` frames are kept and the rest is replaced by a frame like `... 12 more causes`.
+    ///
+    /// Chains with at most `max_depth` frames are returned unchanged.
+    /// The outermost frame is always kept, so a `max_depth` below 2 behaves like 2.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let mut error = ErrorMessage::new("Failed to parse value");
+    /// for _ in 0..100 {
+    ///     error = error.context("Failed to parse object");
+    /// }
+    /// let error = error.truncate_chain(3);
+    /// assert_eq!(error.to_string(), "Failed to parse object
+    ///   caused by: Failed to parse object
+    ///   caused by: ... 99 more causes");
+    /// ```
+    pub fn truncate_chain(self, max_depth: usize) -> ErrorMessage {
+        let max_depth = max_depth.max(2);
+        let (mut frames, leaf) = self.into_frames();
+        let depth = frames.len() + usize::from(leaf.is_some());
+        if depth <= max_depth {
+            return ErrorMessage::from_frames(frames, leaf);
+        }
+        frames.truncate(max_depth - 1);
+        frames.push(ErrorMessage::frame(more_causes(depth - frames.len()).into()));
+        ErrorMessage::from_frames(frames, None)
+    }
+
+    /// Collapse runs of adjacent frames with the same message into a single frame with a repetition count like `(x12)`,
+    /// for example after a retry loop added the same context for every attempt.
+    ///
+    /// Only adjacent frames are collapsed. A collapsed frame keeps the metadata of the outermost frame of its run.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let mut error = ErrorMessage::new("Connection refused");
+    /// for _ in 0..3 {
+    ///     error = error.context("Failed to send request");
+    /// }
+    /// let error = error.context("Failed to sync").dedup_chain();
+    /// assert_eq!(error.to_string(), "Failed to sync
+    ///   caused by: Failed to send request (x3)
+    ///   caused by: Connection refused");
+    /// ```
+    pub fn dedup_chain(self) -> ErrorMessage {
+        let (frames, leaf) = self.into_frames();
+        let mut deduped: Vec<(ErrorMessage, usize)> = Vec::with_capacity(frames.len());
+        for frame in frames {
+            match deduped.last_mut() {
+                Some((previous, count)) if previous.message == frame.message => *count += 1,
+                _ => deduped.push((frame, 1)),
+            }
+        }
+        let frames = deduped
+            .into_iter()
+            .map(|(frame, count)| if count > 1 { frame.map_message(|message| format!("{message} (x{count})")) } else { frame })
+            .collect();
+        ErrorMessage::from_frames(frames, leaf)
+    }
+
+    /// Returns only the outermost message, without any of its causes.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::with_context("Failed to read file", io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(error.message(), "Failed to read file");
+    /// ```
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Consumes the [ErrorMessage] and returns only the outermost message, without any of its causes.
+    pub fn into_message(mut self) -> String {
+        std::mem::take(&mut self.message).into_owned()
+    }
+
+    /// Consumes the [ErrorMessage] and returns its immediate cause, discarding the outermost message.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::with_context("Failed to read file", io::Error::from(io::ErrorKind::NotFound));
+    /// let cause = error.into_cause().unwrap().downcast::<io::Error>().unwrap();
+    /// assert_eq!(cause.kind(), io::ErrorKind::NotFound);
+    /// ```
+    #[cfg(not(feature = "send"))]
+    pub fn into_cause(mut self) -> Option<Box<dyn Error>> {
+        self.cause.take().map(Cause::into_boxed)
+    }
+
+    /// Consumes the [ErrorMessage] and returns its immediate cause, discarding the outermost message.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::with_context("Failed to read file", io::Error::from(io::ErrorKind::NotFound));
+    /// let cause = error.into_cause().unwrap().downcast::<io::Error>().unwrap();
+    /// assert_eq!(cause.kind(), io::ErrorKind::NotFound);
+    /// ```
+    #[cfg(feature = "send")]
+    pub fn into_cause(mut self) -> Option<Box<dyn Error + Send>> {
+        self.cause.take().map(Cause::into_boxed)
+    }
+
+    /// Consumes the [ErrorMessage] and returns the outermost message and its immediate cause.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::with_context("Failed to read file", io::Error::from(io::ErrorKind::NotFound));
+    /// let (message, cause) = error.into_parts();
+    /// assert_eq!(message, "Failed to read file");
+    /// assert!(cause.unwrap().is::<io::Error>());
+    /// ```
+    #[cfg(not(feature = "send"))]
+    pub fn into_parts(mut self) -> (String, Option<Box<dyn Error>>) {
+        (std::mem::take(&mut self.message).into_owned(), self.cause.take().map(Cause::into_boxed))
+    }
+
+    /// Consumes the [ErrorMessage] and returns the outermost message and its immediate cause.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::with_context("Failed to read file", io::Error::from(io::ErrorKind::NotFound));
+    /// let (message, cause) = error.into_parts();
+    /// assert_eq!(message, "Failed to read file");
+    /// assert!(cause.unwrap().is::<io::Error>());
+    /// ```
+    #[cfg(feature = "send")]
+    pub fn into_parts(mut self) -> (String, Option<Box<dyn Error + Send>>) {
+        (std::mem::take(&mut self.message).into_owned(), self.cause.take().map(Cause::into_boxed))
+    }
+
+    /// Returns every frame of the chain as it appears in the Display output, outermost first,
+    /// but without the `caused by: ` prefix and indentation.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_err_context("Failed to read file");
+    /// assert_eq!(result.unwrap_err().to_lines(), ["Failed to read file", "Kind(NotFound)"]);
+    /// ```
+    pub fn to_lines(&self) -> Vec<String> {
+        self.frames().map(|frame| frame.to_string()).collect()
+    }
+
+    /// Returns the immediate cause of this frame.
+    /// That is either the next [ErrorMessage] of the chain or the foreign error at its end.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::with_context("Failed to read file", io::Error::from(io::ErrorKind::NotFound));
+    /// let cause = error.cause().and_then(|cause| cause.downcast_ref::<io::Error>());
+    /// assert_eq!(cause.map(io::Error::kind), Some(io::ErrorKind::NotFound));
+    /// ```
+    #[cfg(not(feature = "send"))]
+    pub fn cause(&self) -> Option<&(dyn Error + 'static)> {
+        self.cause.as_ref().map(|cause| cause.frame().as_error())
+    }
+
+    /// Returns the immediate cause of this frame.
+    /// That is either the next [ErrorMessage] of the chain or the foreign error at its end.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::with_context("Failed to read file", io::Error::from(io::ErrorKind::NotFound));
+    /// let cause = error.cause().and_then(|cause| cause.downcast_ref::<io::Error>());
+    /// assert_eq!(cause.map(io::Error::kind), Some(io::ErrorKind::NotFound));
+    /// ```
+    #[cfg(feature = "send")]
+    pub fn cause(&self) -> Option<&(dyn Error + Send + 'static)> {
+        match self.cause.as_ref()? {
+            Cause::Message(error) => Some(&**error),
+            Cause::Other(cause) => Some(&**cause),
+        }
+    }
+
+    /// Returns the innermost error of the chain.
+    /// That is either the foreign error at its end or the last [ErrorMessage], if it has no cause.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_err_context("Failed to read file")
+    ///     .with_err_context("Failed to load configuration");
+    /// let root = result.unwrap_err().root_cause().downcast_ref::<io::Error>().map(io::Error::kind);
+    /// assert_eq!(root, Some(io::ErrorKind::NotFound));
+    /// ```
+    pub fn root_cause(&self) -> &(dyn Error + 'static) {
+        self.frames().last().expect("A chain always contains at least one frame").as_error()
+    }
+
+    /// Record the source code [Location] this frame was created at.
+    ///
+    /// This is what the [err_context](crate::err_context) macro uses under the hood.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::{ErrorMessage, Location};
+    /// let error = ErrorMessage::new("Error description")
+    ///     .with_location(Location::new(file!(), line!(), column!(), Some(module_path!())));
+    /// # assert_eq!(error.location().unwrap().file(), file!());
+    /// ```
+    #[cfg(any(feature = "macros", feature = "location"))]
+    pub fn with_location(mut self, location: Location) -> ErrorMessage {
+        self.metadata_mut().location = Some(location);
+        self
+    }
+
+    /// Returns the [Location] this frame was created at, if one was recorded.
+    ///
+    /// With the `location` feature, every constructor records the location of its caller.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::{ErrorMessage, Location};
+    /// let error = ErrorMessage::new("Value is missing").with_location(Location::new(file!(), line!(), column!(), None));
+    /// assert_eq!(error.location().unwrap().file(), file!());
+    /// ```
+    #[cfg(any(feature = "macros", feature = "location"))]
+    pub fn location(&self) -> Option<&Location> {
+        self.metadata.as_ref().and_then(|metadata| metadata.location.as_ref())
+    }
+
+    /// Returns the [Backtrace], that was captured when the innermost [ErrorMessage] of the chain was created.
+    ///
+    /// Like [Backtrace::capture], this only captures something, if the `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE`
+    /// environment variables enable it, so there is no cost otherwise.
+    /// [display_verbosity](ErrorMessage::display_verbosity) shows it at level 2.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+    /// if let Some(backtrace) = error.backtrace() {
+    ///     eprintln!("{backtrace}");
+    /// }
+    /// ```
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.frames().filter_map(Frame::message).find_map(|frame| frame.metadata.as_ref().and_then(|metadata| metadata.backtrace.as_ref()))
+    }
+
+    /// Returns the [SpanTrace](tracing_error::SpanTrace) of the spans, that were entered,
+    /// when the innermost [ErrorMessage] of the chain was created.
+    ///
+    /// It is only captured, if the current subscriber contains an [ErrorLayer](tracing_error::ErrorLayer).
+    /// [display_verbosity](ErrorMessage::display_verbosity) shows it at level 2.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// use tracing_subscriber::layer::SubscriberExt;
+    /// let subscriber = tracing_subscriber::registry().with(tracing_error::ErrorLayer::default());
+    /// let error = tracing::subscriber::with_default(subscriber, || {
+    ///     let _span = tracing::info_span!("load_config", path = "/etc/app.toml").entered();
+    ///     ErrorMessage::new("Configuration file is missing")
+    /// });
+    /// assert!(error.span_trace().is_some());
+    /// assert!(ErrorMessage::new("No subscriber").span_trace().is_none());
+    /// ```
+    #[cfg(feature = "spantrace")]
+    pub fn span_trace(&self) -> Option<&tracing_error::SpanTrace> {
+        self.frames().filter_map(Frame::message).find_map(|frame| frame.metadata.as_ref().and_then(|metadata| metadata.span_trace.as_ref()))
+    }
+
+    /// Returns the name of the thread, that created the innermost [ErrorMessage] of the chain, if that thread has a name.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let thread = std::thread::Builder::new().name("worker-3".to_string());
+    /// let name = thread.spawn(|| ErrorMessage::new("Connection refused").thread_name().map(str::to_string)).unwrap().join().unwrap();
+    /// assert_eq!(name.as_deref(), Some("worker-3"));
+    /// ```
+    #[cfg(feature = "thread_info")]
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_info().and_then(|thread| thread.name.as_deref())
+    }
+
+    /// Returns the [ThreadId](std::thread::ThreadId) of the thread, that created the innermost [ErrorMessage] of the chain.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+    /// assert_eq!(error.thread_id(), Some(std::thread::current().id()));
+    /// ```
+    #[cfg(feature = "thread_info")]
+    pub fn thread_id(&self) -> Option<std::thread::ThreadId> {
+        self.thread_info().map(|thread| thread.id)
+    }
+
+    /// The thread recorded by the innermost [ErrorMessage], which is the only one, that records it
+    #[cfg(feature = "thread_info")]
+    pub(crate) fn thread_info(&self) -> Option<&ThreadInfo> {
+        self.frames().filter_map(Frame::message).find_map(|frame| frame.metadata.as_ref().and_then(|metadata| metadata.thread.as_ref()))
+    }
+
+    /// Attach a short identifier like `CFG001` to this frame, so it can be looked up in documentation.
+    /// It is shown as a prefix of the message.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Configuration file is missing").with_id("CFG001");
+    /// assert_eq!(error.to_string(), "[CFG001] Configuration file is missing");
+    /// ```
+    pub fn with_id(mut self, id: impl Into<Cow<'static, str>>) -> ErrorMessage {
+        self.metadata_mut().id = Some(id.into());
+        self
+    }
+
+    /// Returns the identifier of the outermost frame, that has one.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<(), _> = ErrorMessage::err("Configuration file is missing")
+    ///     .map_err(|error| error.with_id("CFG001"))
+    ///     .with_err_context("Failed to start the program");
+    /// assert_eq!(result.unwrap_err().id(), Some("CFG001"));
+    /// ```
+    pub fn id(&self) -> Option<&str> {
+        self.ids().next()
+    }
+
+    /// Returns the identifiers of all frames in the chain, outermost first.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.frames().filter_map(Frame::message).filter_map(ErrorMessage::frame_id)
+    }
+
+    /// Attach a machine-readable error code like `E1042` to this frame, for example for triaging reports.
+    /// It is shown as a prefix of the message, after the [identifier](ErrorMessage::with_id).
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Quota exceeded").with_code("E1042");
+    /// assert_eq!(error.to_string(), "[E1042] Quota exceeded");
+    /// ```
+    pub fn with_code(mut self, code: impl ToString) -> ErrorMessage {
+        self.metadata_mut().code = Some(code.to_string());
+        self
+    }
+
+    /// Returns the error code of the innermost frame, that has one,
+    /// so the code closest to the root cause survives any amount of context added on top of it.
+    /// It isn't called `code`, so it doesn't shadow `code` of miette's `Diagnostic` with the `miette` feature.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::prelude::*;
+    /// let result: Result<(), _> = ErrorMessage::err("Quota exceeded")
+    ///     .map_err(|error| error.with_code("E1042"))
+    ///     .with_err_context_code("E0001", "Failed to upload file");
+    /// assert_eq!(result.unwrap_err().error_code(), Some("E1042"));
+    /// ```
+    pub fn error_code(&self) -> Option<&str> {
+        self.frames().filter_map(Frame::message).filter_map(ErrorMessage::frame_code).last()
+    }
+
+    /// Attach a machine-readable detail like `path=/etc/app.toml` to this frame.
+    /// Details are shown as indented `key: value` lines below the message, in the order they were added.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Failed to read file")
+    ///     .with_detail("path", "/etc/app.toml")
+    ///     .with_detail("attempt", 3);
+    /// assert_eq!(error.to_string(), "Failed to read file\n  path: /etc/app.toml\n  attempt: 3");
+    /// ```
+    pub fn with_detail(mut self, key: impl ToString, value: impl ToString) -> ErrorMessage {
+        self.metadata_mut().details.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Attach all pairs as details with [with_detail](ErrorMessage::with_detail)
+    pub(crate) fn with_details<K: ToString, V: ToString>(self, details: impl IntoIterator<Item = (K, V)>) -> ErrorMessage {
+        details.into_iter().fold(self, |error, (key, value)| error.with_detail(key, value))
+    }
+
+    /// Returns the details of this frame, in the order they were added.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Failed to read file").with_detail("path", "/etc/app.toml");
+    /// assert_eq!(error.details(), [("path".to_string(), "/etc/app.toml".to_string())]);
+    /// assert!(error.context("Failed to load configuration").details().is_empty());
+    /// ```
+    pub fn details(&self) -> &[(String, String)] {
+        self.metadata.as_ref().map_or(&[], |metadata| &metadata.details)
+    }
+
+    /// Attach a suggestion, what the user can do about this error.
+    /// Hints of all frames are shown as `hint: ...` lines below the whole chain.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Configuration file is missing")
+    ///     .with_hint("run `myapp init` to create it")
+    ///     .context("Failed to start the program");
+    /// assert_eq!(error.to_string(), "\
+    /// Failed to start the program
+    ///   caused by: Configuration file is missing
+    ///   hint: run `myapp init` to create it");
+    /// ```
+    pub fn with_hint(mut self, hint: impl ToString) -> ErrorMessage {
+        self.metadata_mut().hints.push(hint.to_string());
+        self
+    }
+
+    /// Returns the hints of all frames in the chain, outermost first and in the order they were added per frame.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused")
+    ///     .with_hint("check that the server is running")
+    ///     .context("Failed to sync")
+    ///     .with_hint("run with `--offline` to skip syncing");
+    /// assert_eq!(error.hints().collect::<Vec<_>>(), ["run with `--offline` to skip syncing", "check that the server is running"]);
+    /// ```
+    pub fn hints(&self) -> impl Iterator<Item = &str> {
+        self.frames().filter_map(Frame::message).flat_map(ErrorMessage::frame_hints).map(String::as_str)
+    }
+
+    /// The hints of this frame alone
+    pub(crate) fn frame_hints(&self) -> &[String] {
+        self.metadata.as_ref().map_or(&[], |metadata| &metadata.hints)
+    }
+
+    /// Attach a value of any type to this frame, for example a request id or a hint for a retry policy.
+    /// It can be retrieved with [get_attachment](ErrorMessage::get_attachment) from any frame above it.
+    ///
+    /// Attachments are not part of the Display, Debug or serde output.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// struct RequestId(u64);
+    /// let error = ErrorMessage::new("Connection reset")
+    ///     .attach(RequestId(42))
+    ///     .context("Failed to handle request");
+    /// assert_eq!(error.get_attachment::<RequestId>().map(|id| id.0), Some(42));
+    /// assert_eq!(error.to_string(), "Failed to handle request\n  caused by: Connection reset");
+    /// ```
+    pub fn attach<T: Any + Send + Sync + 'static>(mut self, value: T) -> ErrorMessage {
+        self.metadata_mut().attachments.push(Box::new(value));
+        self
+    }
+
+    /// Returns the first attachment of type `T`, searching every frame of the chain from the outside in.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection reset").attach(3_u32);
+    /// assert_eq!(error.get_attachment::<u32>(), Some(&3));
+    /// assert_eq!(error.get_attachment::<String>(), None);
+    /// ```
+    pub fn get_attachment<T: Any>(&self) -> Option<&T> {
+        self.frames()
+            .filter_map(Frame::message)
+            .filter_map(|frame| frame.metadata.as_ref())
+            .flat_map(|metadata| &metadata.attachments)
+            .find_map(|attachment| attachment.downcast_ref::<T>())
+    }
+
+    /// Mark this frame as transient, meaning the operation might succeed, if it is retried.
+    /// The mark stays visible, when the error is wrapped with more context.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection timed out")
+    ///     .transient()
+    ///     .context("Failed to fetch data");
+    /// assert!(error.is_transient());
+    /// ```
+    pub fn transient(self) -> ErrorMessage {
+        self.with_transient(true)
+    }
+
+    /// Set or clear the transient mark of this frame. See [transient](ErrorMessage::transient).
+    pub fn with_transient(mut self, transient: bool) -> ErrorMessage {
+        if transient || self.metadata.is_some() {
+            self.metadata_mut().transient = transient;
+        }
+        self
+    }
+
+    /// Returns true, if any frame of the chain was marked as [transient](ErrorMessage::transient).
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// assert!(!ErrorMessage::new("Invalid input").is_transient());
+    /// ```
+    pub fn is_transient(&self) -> bool {
+        self.frames().filter_map(Frame::message).any(ErrorMessage::frame_transient)
+    }
+
+    /// Whether this frame alone was marked as transient
+    pub(crate) fn frame_transient(&self) -> bool {
+        self.metadata.as_ref().is_some_and(|metadata| metadata.transient)
+    }
+
+    /// Attach the code the program should exit with because of this error, for example `2` for usage errors.
+    /// [Report](crate::Report) uses it instead of the default code 1.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Unknown flag '--colour'")
+    ///     .with_exit_code(2)
+    ///     .context("Failed to parse arguments");
+    /// assert_eq!(error.exit_code(), Some(2));
+    /// ```
+    pub fn with_exit_code(mut self, code: u8) -> ErrorMessage {
+        self.metadata_mut().exit_code = Some(code);
+        self
+    }
+
+    /// Returns the exit code of the outermost frame that has one,
+    /// so outer layers can override the code of the errors they wrap.
+    ///
+    /// Example:
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused").with_exit_code(75);
+    /// assert_eq!(error.context("Failed to sync").with_exit_code(1).exit_code(), Some(1));
+    /// assert_eq!(ErrorMessage::new("Connection refused").exit_code(), None);
+    /// ```
+    pub fn exit_code(&self) -> Option<u8> {
+        self.frames()
+            .filter_map(Frame::message)
+            .find_map(|frame| frame.metadata.as_ref().and_then(|metadata| metadata.exit_code))
+    }
+
+    /// Iterate over this [ErrorMessage], all nested [ErrorMessages](ErrorMessage) and the foreign cause at the end of the chain.
+    pub(crate) fn frames(&self) -> impl Iterator<Item = Frame<'_>> {
+        std::iter::successors(Some(Frame::Message(self)), |frame| frame.next_frame())
+    }
+
+    /// The identifier of this frame alone
+    pub(crate) fn frame_id(&self) -> Option<&str> {
+        self.metadata.as_ref().and_then(|metadata| metadata.id.as_deref())
+    }
+
+    /// The error code of this frame alone
+    pub(crate) fn frame_code(&self) -> Option<&str> {
+        self.metadata.as_ref().and_then(|metadata| metadata.code.as_deref())
+    }
+
+    /// The [Metadata] of this frame, which is allocated on first use
+    pub(crate) fn metadata_mut(&mut self) -> &mut Metadata {
+        self.metadata.get_or_insert_default()
+    }
+
+    /// The last [ErrorMessage] of the chain
+    pub(crate) fn innermost_mut(&mut self) -> &mut ErrorMessage {
+        let mut frame = self;
+        while matches!(frame.cause, Some(Cause::Message(_))) {
+            let Some(Cause::Message(next)) = &mut frame.cause else { unreachable!("The cause was checked to be an ErrorMessage") };
+            frame = next;
+        }
+        frame
+    }
+
+    /// Take the chain apart into its [ErrorMessages](ErrorMessage) without causes, outermost first,
+    /// and the foreign error at its end.
+    pub(crate) fn into_frames(self) -> (Vec<ErrorMessage>, Option<BoxedError>) {
+        let mut frames = Vec::new();
+        let mut current = self;
+        loop {
+            let cause = current.cause.take();
+            frames.push(current);
+            match cause {
+                Some(Cause::Message(next)) => current = *next,
+                Some(Cause::Other(leaf)) => return (frames, Some(leaf)),
+                None => return (frames, None),
+            }
+        }
+    }
+
+    /// The reverse of [into_frames](ErrorMessage::into_frames)
+    pub(crate) fn from_frames(mut frames: Vec<ErrorMessage>, leaf: Option<BoxedError>) -> ErrorMessage {
+        let mut error = frames.pop().expect("A chain always contains at least one frame");
+        error.cause = leaf.map(Cause::from_boxed);
+        while let Some(mut parent) = frames.pop() {
+            parent.cause = Some(Cause::Message(Box::new(error)));
+            error = parent;
+        }
+        error
+    }
+
+    /// Like [with_context](ErrorMessage::with_context), but for a cause, that is already boxed, so it isn't boxed a second time.
+    /// The `cause_type` is only known, where the cause wasn't boxed yet.
+    #[cfg_attr(feature = "location", track_caller)]
+    pub(crate) fn with_boxed_context(message: Cow<'static, str>, cause: BoxedError, cause_type: Option<&'static str>) -> ErrorMessage {
+        let mut error = ErrorMessage::frame(message);
+        error.cause = Some(Cause::from_boxed(cause));
+        error.cause_type = cause_type;
+        error.created();
+        error
+    }
+
+    /// Like [with_context](ErrorMessage::with_context), but for an error, that can't be moved into the chain.
+    /// The cause is a stand-in, that only keeps the Debug output of the error.
+    #[cfg_attr(feature = "location", track_caller)]
+    pub(crate) fn with_debug_context<E: Debug>(message: Cow<'static, str>, error: &E) -> ErrorMessage {
+        let cause = DebugText(format!("{error:?}"));
+        ErrorMessage::with_boxed_context(message, Box::new(cause), Some(std::any::type_name::<E>()))
+    }
+
+    /// A single frame without a cause and without running the creation hook.
+    #[cfg_attr(not(feature = "timestamp"), allow(unused_mut))]
+    fn frame(message: Cow<'static, str>) -> ErrorMessage {
+        let mut error = ErrorMessage {
+            message,
+            cause: None,
+            cause_type: None,
+            metadata: None,
+        };
+        #[cfg(feature = "timestamp")]
+        {
+            error.metadata_mut().timestamp = Some(std::time::SystemTime::now());
+        }
+        error
+    }
+
+    /// Runs whenever a constructor created a frame.
+    /// Only a frame, that starts a new chain, is reported:
+    /// Wrapping existing [ErrorMessages](ErrorMessage) adds a frame to their chains, so they are not reported again.
+    #[cfg_attr(feature = "location", track_caller)]
+    fn created(&mut self) {
+        #[cfg(feature = "location")]
+        {
+            self.metadata_mut().location = Some(Location::from(std::panic::Location::caller()));
+        }
+        if self.branches().is_empty() && !matches!(self.cause, Some(Cause::Message(_))) {
+            #[cfg(feature = "thread_info")]
+            {
+                let thread = std::thread::current();
+                self.metadata_mut().thread = Some(ThreadInfo { name: thread.name().map(str::to_string), id: thread.id() });
+            }
+            #[cfg(feature = "backtrace")]
+            {
+                let backtrace = Backtrace::capture();
+                if backtrace.status() == BacktraceStatus::Captured {
+                    self.metadata_mut().backtrace = Some(backtrace);
+                }
+            }
+            #[cfg(feature = "spantrace")]
+            {
+                let span_trace = tracing_error::SpanTrace::capture();
+                if span_trace.status() == tracing_error::SpanTraceStatus::CAPTURED {
+                    self.metadata_mut().span_trace = Some(span_trace);
+                }
+            }
+            #[cfg(feature = "stats")]
+            crate::stats::record(self);
+            crate::hook::run(self);
+        }
+    }
+}
+
+/// [source](Error::source) returns the cause of this frame, so generic reporters can walk the chain.
+///
+/// Keep in mind, that the Display output of an [ErrorMessage] already contains its whole chain.
+/// A reporter that prints the Display output of every [source](Error::source) will print the lower frames several times.
+/// Print only the outermost error or the [message](ErrorMessage::message) of each frame in that case.
+impl Error for ErrorMessage {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.cause.as_ref().map(|cause| cause.frame().as_error())
+    }
+}
+
+/// Creates an [ErrorMessage] without a cause, like [new](ErrorMessage::new), so plain string errors work with `?`.
+///
+/// ```rust
+/// use errors_with_context::ErrorMessage;
+/// fn parse(input: &str) -> Result<u8, ErrorMessage> {
+///     if input.is_empty() {
+///         Err("Input is empty")?
+///     }
+///     Ok(input.len() as u8)
+/// }
+/// assert_eq!(parse("").unwrap_err().to_string(), "Input is empty");
+/// ```
+impl From<&str> for ErrorMessage {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn from(message: &str) -> ErrorMessage {
+        ErrorMessage::new(message)
+    }
+}
+
+/// Creates an [ErrorMessage] without a cause, like [new](ErrorMessage::new), so plain string errors work with `?`.
+///
+/// ```rust
+/// use errors_with_context::ErrorMessage;
+/// fn parse(input: &str) -> Result<u8, ErrorMessage> {
+///     Ok(input.parse().map_err(|_| format!("'{input}' is not a number"))?)
+/// }
+/// assert_eq!(parse("four").unwrap_err().to_string(), "'four' is not a number");
+/// ```
+impl From<String> for ErrorMessage {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn from(message: String) -> ErrorMessage {
+        ErrorMessage::new(message)
+    }
+}
+
+/// `{}` writes the whole chain with one frame per line.
+///
+/// The alternate flag `{:#}` writes the chain on a single line instead, just like [to_compact_string](ErrorMessage::to_compact_string).
+/// This fits into line based log formats:
+/// ```rust
+/// use errors_with_context::ErrorMessage;
+/// let error = ErrorMessage::new("I/O Error").context("Failed to read file");
+/// assert_eq!(format!("{error}"), "Failed to read file\n  caused by: I/O Error");
+/// assert_eq!(format!("{error:#}"), "Failed to read file: I/O Error");
+/// ```
+/// ```rust
+/// # use errors_with_context::ErrorMessage;
+/// # let error = ErrorMessage::new("I/O Error").context("Failed to read file");
+/// tracing::error!("{:#}", error);
+/// ```
+///
+/// The precision flag limits the number of frames, that are written, for example `{:.2}` writes only the first two frames:
+/// ```rust
+/// use errors_with_context::ErrorMessage;
+/// let error = ErrorMessage::new("I/O Error").context("Failed to read file").context("Failed to load configuration");
+/// assert_eq!(format!("{error:.2}"), "Failed to load configuration\n  caused by: Failed to read file\n  ... 1 more cause");
+/// ```
+impl Display for ErrorMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            self.write_compact(": ", f)
+        } else if let Some(depth) = f.precision() {
+            self.write_limited(depth, f)
+        } else {
+            self.write_chain(f)
+        }
+    }
+}
+
+/// Drops the chain one frame after the other, so very deep chains don't overflow the stack
+impl Drop for ErrorMessage {
+    fn drop(&mut self) {
+        let mut cause = self.cause.take();
+        while let Some(Cause::Message(mut next)) = cause {
+            cause = next.cause.take();
+        }
+    }
+}
+
+/// Two [ErrorMessages](ErrorMessage) are equal, if their chains have the same depth and every frame has the same message.
+///
+/// The comparison is only based on messages.
+/// The types of the errors, identifiers or any other metadata are not compared.
+/// [Branches](ErrorMessage::branches) are compared in order.
+/// A foreign error at the end of a chain is compared by its Display output.
+///
+/// ```rust
+/// # use std::convert::Infallible;
+/// # use std::io;
+/// use errors_with_context::prelude::*;
+/// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+///     .with_err_context("Failed to read file");
+/// assert_eq!(result.unwrap_err(), ErrorMessage::new("entity not found").context("Failed to read file"));
+/// ```
+impl PartialEq for ErrorMessage {
+    fn eq(&self, other: &ErrorMessage) -> bool {
+        fn text(frame: Frame) -> Cow<str> {
+            match frame {
+                Frame::Message(error) => Cow::Borrowed(&error.message),
+                Frame::Cause(cause) => Cow::Owned(cause.to_string()),
+            }
+        }
+        fn branches(frame: Frame<'_>) -> &[ErrorMessage] {
+            frame.message().map(ErrorMessage::branches).unwrap_or_default()
+        }
+        let mut frames = self.frames();
+        let mut other_frames = other.frames();
+        loop {
+            match (frames.next(), other_frames.next()) {
+                (Some(frame), Some(other_frame)) if text(frame) == text(other_frame) && branches(frame) == branches(other_frame) => {}
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "pretty_debug_errors"))]
+impl Debug for ErrorMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_structure(f)
+    }
+}
+
+/// `{:?}` writes the same as Display, while `{:#?}` writes the structure of the chain with one field per line
+#[cfg(feature = "pretty_debug_errors")]
+impl Debug for ErrorMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() { self.fmt_structure(f) } else { self.write_chain(f) }
+    }
+}
+
+impl ErrorMessage {
+    /// Writes the same nested structure as `#[derive(Debug)]` would,
+    /// but walks the chain in a loop, so very deep chains don't overflow the stack.
+    fn fmt_structure(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use fmt::Write;
+        let pretty = f.alternate();
+        let mut w = Indented { f, level: 0, on_newline: false };
+        let mut depth = 0;
+        let mut frame = self;
+        let leaf = loop {
+            w.write_str(if pretty { "ErrorMessage {\n" } else { "ErrorMessage { " })?;
+            w.level = depth + 1;
+            for (name, value) in frame.debug_fields() {
+                if pretty {
+                    writeln!(w, "{name}: {value:#?},")?;
+                } else {
+                    write!(w, "{name}: {value:?}, ")?;
+                }
+            }
+            w.write_str("cause: ")?;
+            depth += 1;
+            match &frame.cause {
+                Some(Cause::Message(next)) => frame = next,
+                Some(Cause::Other(cause)) => break Some(cause),
+                None => break None,
+            }
+        };
+        match (leaf, pretty) {
+            (Some(leaf), true) => writeln!(w, "{:#?},", Some(leaf))?,
+            (Some(leaf), false) => write!(w, "{:?}", Some(leaf))?,
+            (None, true) => w.write_str("None,\n")?,
+            (None, false) => w.write_str("None")?,
+        }
+        for level in (0..depth).rev() {
+            w.level = level;
+            match (pretty, level) {
+                (true, 0) => w.write_str("}")?,
+                (true, _) => w.write_str("},\n")?,
+                (false, _) => w.write_str(" }")?,
+            }
+        }
+        Ok(())
+    }
+
+    /// The fields of this frame for the Debug output, without its cause
+    fn debug_fields(&self) -> Vec<(&'static str, &dyn Debug)> {
+        let mut fields: Vec<(&'static str, &dyn Debug)> = vec![("message", &self.message)];
+        if let Some(metadata) = &self.metadata {
+            if let Some(id) = &metadata.id {
+                fields.push(("id", id));
+            }
+            if let Some(code) = &metadata.code {
+                fields.push(("code", code));
+            }
+            if !metadata.details.is_empty() {
+                fields.push(("details", &metadata.details));
+            }
+            if !metadata.hints.is_empty() {
+                fields.push(("hints", &metadata.hints));
+            }
+            if metadata.transient {
+                fields.push(("transient", &metadata.transient));
+            }
+            if let Some(exit_code) = &metadata.exit_code {
+                fields.push(("exit_code", exit_code));
+            }
+            #[cfg(feature = "http")]
+            if let Some(status) = &metadata.status {
+                fields.push(("status", status));
+            }
+            #[cfg(any(feature = "macros", feature = "location"))]
+            if let Some(location) = &metadata.location {
+                fields.push(("location", location));
+            }
+            #[cfg(feature = "timestamp")]
+            if let Some(timestamp) = &metadata.timestamp {
+                fields.push(("timestamp", timestamp));
+            }
+            if !metadata.branches.is_empty() {
+                fields.push(("branches", &metadata.branches));
+            }
+        }
+        fields
+    }
+}
+
+/// Indents every line written through it by `level` steps of four spaces, like nested pretty Debug output
+struct Indented<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    level: usize,
+    on_newline: bool,
+}
+
+impl fmt::Write for Indented<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.on_newline {
+                for _ in 0..self.level {
+                    self.f.write_str("    ")?;
+                }
+            }
+            self.on_newline = line.ends_with('\n');
+            self.f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single entry of a chain: Either one of the [ErrorMessages](ErrorMessage) or the foreign error at its end
+#[derive(Clone, Copy)]
+pub(crate) enum Frame<'a> {
+    Message(&'a ErrorMessage),
+    Cause(&'a (dyn Error + 'static)),
+}
+
+impl<'a> Frame<'a> {
+    /// The frame below this one in the chain
+    pub(crate) fn next_frame(self) -> Option<Frame<'a>> {
+        match self {
+            Frame::Message(error) => error.cause.as_ref().map(Cause::frame),
+            Frame::Cause(_) => None,
+        }
+    }
+
+    pub(crate) fn as_error(self) -> &'a (dyn Error + 'static) {
+        match self {
+            Frame::Message(error) => error,
+            Frame::Cause(cause) => cause,
+        }
+    }
+
+    pub(crate) fn message(self) -> Option<&'a ErrorMessage> {
+        match self {
+            Frame::Message(error) => Some(error),
+            Frame::Cause(_) => None,
+        }
+    }
+}
+
+/// Writes the frame the way it appears in the Display output of a chain
+impl Display for Frame<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Frame::Message(error) => {
+                if let Some(id) = error.frame_id() {
+                    write!(f, "[{id}] ")?;
+                }
+                if let Some(code) = error.frame_code() {
+                    write!(f, "[{code}] ")?;
+                }
+                f.write_str(&error.message)
+            }
+            Frame::Cause(cause) => Debug::fmt(cause, f),
         }
-    } else {
-        Debug::fmt(error, f)?;
     }
-    Ok(())
 }
@@ -0,0 +1,119 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// [WithContext](crate::WithContext) for `Result<T, eyre::Report>`.
+///
+/// [eyre::Report] doesn't implement [Error], so [WithContext](crate::WithContext) can't be implemented for it directly.
+/// This trait has the same methods, so it works the same way, once it is imported, for example with the [prelude](crate::prelude).
+/// Every context layer of the [eyre::Report] becomes a frame and its root cause becomes the cause at the end of the chain,
+/// as if the contexts had been added with this crate all along.
+pub trait EyreWithContext<T> {
+    /// Like [with_err_context](crate::WithContext::with_err_context), but for `Result<T, eyre::Report>`.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use eyre::WrapErr;
+    /// use errors_with_context::prelude::*;
+    /// fn read_config() -> eyre::Result<String> {
+    ///     Err(io::Error::from(io::ErrorKind::NotFound)).wrap_err("Failed to read config")
+    /// }
+    /// let result = read_config().with_err_context("Failed to start");
+    /// assert_eq!(result.unwrap_err().to_string(), "\
+    /// Failed to start
+    ///   caused by: Failed to read config
+    ///   caused by: Kind(NotFound)");
+    /// ```
+    fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+
+    /// Like [with_dyn_err_context](crate::WithContext::with_dyn_err_context), but for `Result<T, eyre::Report>`.
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage>;
+
+    /// Like [with_err_context_id](crate::WithContext::with_err_context_id), but for `Result<T, eyre::Report>`.
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage>;
+}
+
+impl<T> EyreWithContext<T> for Result<T, eyre::Report> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(ErrorMessage::from_eyre(error).context(context)),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(ErrorMessage::from_eyre(error).context_static(context())),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_id(id))
+    }
+}
+
+impl ErrorMessage {
+    /// Convert the chain into an [eyre::Report], that has one context layer for every frame.
+    ///
+    /// `{:#}` and `{:?}` of the [eyre::Report] show every frame, just like [chain](eyre::Report::chain) lists them.
+    /// eyre needs its errors to be [Sync], which the cause at the end of the chain doesn't have to be,
+    /// so the [root_cause](eyre::Report::root_cause) only contains the text of the innermost frame.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused").context("Failed to sync").into_eyre();
+    /// assert_eq!(format!("{error:#}"), "Failed to sync: Connection refused");
+    /// assert_eq!(error.root_cause().to_string(), "Connection refused");
+    /// ```
+    pub fn into_eyre(self) -> eyre::Report {
+        let mut frames = self.frames().map(|frame| frame.to_string()).collect::<Vec<_>>().into_iter().rev();
+        let root = eyre::Report::msg(frames.next().unwrap_or_default());
+        frames.fold(root, eyre::Report::wrap_err)
+    }
+
+    /// Convert an [eyre::Report] into a chain, where every context layer becomes a frame
+    /// and the root cause becomes the cause at the end of the chain.
+    ///
+    /// ```rust
+    /// use eyre::eyre;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::from_eyre(eyre!("Connection refused").wrap_err("Failed to sync"));
+    /// assert_eq!(error.to_string(), "Failed to sync\n  caused by: \"Connection refused\"");
+    /// ```
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn from_eyre(error: eyre::Report) -> ErrorMessage {
+        let contexts: Vec<String> = error.chain().rev().skip(1).map(|layer| layer.to_string()).collect();
+        let mut contexts = contexts.into_iter();
+        let root = RootCause(error);
+        let mut chain = match contexts.next() {
+            Some(innermost) => ErrorMessage::with_context(innermost, root),
+            None => return ErrorMessage::from_error(root),
+        };
+        for context in contexts {
+            chain = chain.context(context);
+        }
+        chain
+    }
+}
+
+/// Owns the whole [eyre::Report], but only shows its root cause, because the context layers already became frames
+struct RootCause(eyre::Report);
+
+impl Display for RootCause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.0.root_cause(), f)
+    }
+}
+
+impl Debug for RootCause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.0.root_cause(), f)
+    }
+}
+
+impl Error for RootCause {}
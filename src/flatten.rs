@@ -0,0 +1,48 @@
+use crate::error_message::ErrorMessage;
+use crate::WithContext;
+use std::borrow::Cow;
+
+/// [FlattenWithContext] is implemented for nested results like `Result<Result<T, E1>, E2>`,
+/// which come from APIs like `spawn_blocking` or calls over a channel, that can fail on two levels.
+///
+/// Both the outer and the inner error get the same context, so either way the result is a single chain.
+/// An inner [ErrorMessage] just gets another frame on top, like with [context](ErrorMessage::context).
+///
+/// ```rust
+/// # use std::io;
+/// use errors_with_context::prelude::*;
+/// fn call_worker() -> Result<Result<u8, ErrorMessage>, io::Error> {
+///     Ok(ErrorMessage::err("Disk is full"))
+/// }
+/// let error = call_worker().flatten_with_context("Failed to write the backup").unwrap_err();
+/// assert_eq!(error.to_string(), "Failed to write the backup\n  caused by: Disk is full");
+/// ```
+pub trait FlattenWithContext<T> {
+    /// Flatten the results into a single `Result<T, ErrorMessage>` and add the provided context to either error.
+    fn flatten_with_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+
+    /// Like [flatten_with_context](FlattenWithContext::flatten_with_context), but the context is only built in case of an error.
+    fn flatten_with_dyn_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage>;
+}
+
+impl<T, E1, E2> FlattenWithContext<T> for Result<Result<T, E1>, E2>
+where
+    Result<T, E1>: WithContext<T, E1>,
+    Result<T, E2>: WithContext<T, E2>,
+{
+    #[cfg_attr(feature = "location", track_caller)]
+    fn flatten_with_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(inner) => inner.with_err_context(context),
+            Err(error) => Err::<T, E2>(error).with_err_context(context),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn flatten_with_dyn_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(inner) => inner.with_dyn_err_context(context),
+            Err(error) => Err::<T, E2>(error).with_dyn_err_context(context),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::ErrorMessage;
+use std::cell::Cell;
+use std::sync::OnceLock;
+
+type Hook = Box<dyn Fn(&ErrorMessage) + Send + Sync>;
+
+static HOOK: OnceLock<Hook> = OnceLock::new();
+
+thread_local! {
+    static RUNNING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set a function, that is called for every newly created chain, for example to count errors in a metrics system.
+///
+/// The hook runs once per chain, right after its innermost [ErrorMessage] was created by
+/// [new](ErrorMessage::new), [err](ErrorMessage::err), [with_context](ErrorMessage::with_context)
+/// or one of the methods of [WithContext](crate::WithContext).
+/// The chains of [TapWithContext](crate::TapWithContext), that only exist to be observed, are reported as well.
+/// Adding context to an existing [ErrorMessage] doesn't call it again, so every error is seen exactly once,
+/// and combining errors with [from_errors](ErrorMessage::from_errors) doesn't call it for the combined errors a second time.
+///
+/// The hook can only be set once. Returns `false`, if a hook was already set, which stays in place.
+/// Errors created inside of the hook don't call it again.
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use errors_with_context::{set_error_hook, ErrorMessage};
+/// static ERRORS: AtomicUsize = AtomicUsize::new(0);
+///
+/// set_error_hook(|_| {
+///     ERRORS.fetch_add(1, Ordering::Relaxed);
+/// });
+/// let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+/// assert_eq!(ERRORS.load(Ordering::Relaxed), 1);
+/// ```
+pub fn set_error_hook(hook: impl Fn(&ErrorMessage) + Send + Sync + 'static) -> bool {
+    HOOK.set(Box::new(hook)).is_ok()
+}
+
+pub(crate) fn run(error: &ErrorMessage) {
+    let Some(hook) = HOOK.get() else {
+        return;
+    };
+    if RUNNING.replace(true) {
+        return;
+    }
+    /// Resets the flag, even if the hook panics
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            RUNNING.set(false);
+        }
+    }
+    let _reset = Reset;
+    hook(error);
+}
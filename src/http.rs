@@ -0,0 +1,41 @@
+use crate::error_message::{ErrorMessage, Frame};
+use http::StatusCode;
+
+impl ErrorMessage {
+    /// Attach the HTTP status, that a server should respond with because of this error, for example `404` for missing resources.
+    /// The `axum` and `actix` features use it instead of `500 Internal Server Error`.
+    ///
+    /// Example:
+    /// ```rust
+    /// use http::StatusCode;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("No user with id 42")
+    ///     .with_status(StatusCode::NOT_FOUND)
+    ///     .context("Failed to load the user");
+    /// assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+    /// ```
+    pub fn with_status(mut self, status: StatusCode) -> ErrorMessage {
+        self.metadata_mut().status = Some(status);
+        self
+    }
+
+    /// Returns the HTTP status of the outermost frame that has one,
+    /// so outer layers can override the status of the errors they wrap, just like the [exit code](ErrorMessage::exit_code).
+    ///
+    /// Example:
+    /// ```rust
+    /// use http::StatusCode;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused").with_status(StatusCode::BAD_GATEWAY);
+    /// assert_eq!(error.context("Maintenance").with_status(StatusCode::SERVICE_UNAVAILABLE).status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    /// assert_eq!(ErrorMessage::new("Connection refused").status(), None);
+    /// ```
+    pub fn status(&self) -> Option<StatusCode> {
+        self.frames().filter_map(Frame::message).find_map(ErrorMessage::frame_status)
+    }
+
+    /// The HTTP status attached to this frame, without looking at its causes
+    pub(crate) fn frame_status(&self) -> Option<StatusCode> {
+        self.metadata.as_ref().and_then(|metadata| metadata.status)
+    }
+}
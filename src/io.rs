@@ -0,0 +1,113 @@
+use crate::error_message::ErrorMessage;
+use std::io;
+
+impl ErrorMessage {
+    /// Turn an [io::Error] back into an [ErrorMessage].
+    ///
+    /// If the [io::Error] was created from an [ErrorMessage], the original chain is returned as it was,
+    /// instead of wrapping it a second time. Every other [io::Error] is converted like with [from_error](ErrorMessage::from_error).
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::from_io_roundtrip(io::Error::from(io::ErrorKind::NotFound));
+    /// assert_eq!(error.to_string(), "entity not found\n  caused by: Kind(NotFound)");
+    /// # #[cfg(feature = "send")]
+    /// # {
+    /// let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+    /// let error = ErrorMessage::from_io_roundtrip(io::Error::from(error));
+    /// assert_eq!(error.to_string(), "Failed to sync\n  caused by: Connection refused");
+    /// # }
+    /// ```
+    #[cfg_attr(feature = "location", track_caller)]
+    pub fn from_io_roundtrip(error: io::Error) -> ErrorMessage {
+        #[cfg(feature = "send")]
+        if error.get_ref().is_some_and(|inner| inner.is::<IoErrorMessage>()) {
+            return IoErrorMessage::unwrap(error);
+        }
+        ErrorMessage::from_error(error)
+    }
+}
+
+#[cfg(feature = "send")]
+pub use shared::IoErrorMessage;
+
+#[cfg(feature = "send")]
+mod shared {
+    use crate::error_message::ErrorMessage;
+    use std::error::Error;
+    use std::fmt::{self, Debug, Display, Formatter};
+    use std::io;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// The error inside of an [io::Error], that was created from an [ErrorMessage].
+    ///
+    /// An [io::Error] has to be [Sync], which an [ErrorMessage] isn't, because its cause doesn't have to be.
+    /// So it is stored behind a [Mutex], which only needs the `send` feature.
+    /// [get_ref](io::Error::get_ref) and `into_inner` of the [io::Error] can be downcast to this type,
+    /// but [from_io_roundtrip](ErrorMessage::from_io_roundtrip) is usually simpler.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::{ErrorMessage, IoErrorMessage};
+    /// let error = io::Error::from(ErrorMessage::new("Connection refused").context("Failed to sync"));
+    /// let inner = error.get_ref().and_then(|inner| inner.downcast_ref::<IoErrorMessage>()).unwrap();
+    /// assert_eq!(inner.lock().to_string(), "Failed to sync\n  caused by: Connection refused");
+    ///
+    /// let inner = error.into_inner().unwrap().downcast::<IoErrorMessage>().unwrap();
+    /// assert_eq!(inner.into_inner().to_string(), "Failed to sync\n  caused by: Connection refused");
+    /// ```
+    pub struct IoErrorMessage(Mutex<ErrorMessage>);
+
+    impl IoErrorMessage {
+        /// Borrow the chain. A poisoned lock is ignored, because an [ErrorMessage] can't be left in an inconsistent state.
+        pub fn lock(&self) -> MutexGuard<'_, ErrorMessage> {
+            self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
+
+        /// Take the chain out of the wrapper.
+        pub fn into_inner(self) -> ErrorMessage {
+            self.0.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
+
+        /// Only call this after checking, that the [io::Error] contains an [IoErrorMessage]
+        pub(super) fn unwrap(error: io::Error) -> ErrorMessage {
+            let inner = error.into_inner().expect("io::Error contains an ErrorMessage");
+            let inner = inner.downcast::<IoErrorMessage>().unwrap_or_else(|_| unreachable!("io::Error contains an ErrorMessage"));
+            inner.into_inner()
+        }
+    }
+
+    impl Display for IoErrorMessage {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            Display::fmt(&*self.lock(), f)
+        }
+    }
+
+    impl Debug for IoErrorMessage {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            Debug::fmt(&*self.lock(), f)
+        }
+    }
+
+    /// The causes can't be borrowed through the lock, but they are already part of the Display output
+    impl Error for IoErrorMessage {}
+
+    /// Keeps the whole chain inside of an [io::Error] of the kind [Other](io::ErrorKind::Other),
+    /// so it can be recovered with [from_io_roundtrip](ErrorMessage::from_io_roundtrip)
+    /// or by downcasting the inner error to an [IoErrorMessage].
+    /// This conversion only exists with the `send` feature.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = io::Error::from(ErrorMessage::new("Connection refused").context("Failed to sync"));
+    /// assert_eq!(error.kind(), io::ErrorKind::Other);
+    /// assert_eq!(error.to_string(), "Failed to sync\n  caused by: Connection refused");
+    /// ```
+    impl From<ErrorMessage> for io::Error {
+        fn from(error: ErrorMessage) -> io::Error {
+            io::Error::other(IoErrorMessage(Mutex::new(error)))
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::display::more_causes;
+use crate::error_message::{Cause, ErrorMessage};
+use std::fmt::Write;
+
+/// The number of nested frames, that are serialized, before the rest of the chain is summarized in a single frame.
+/// Serializers recurse once per frame, so this keeps very deep chains from overflowing the stack
+/// and the output stays below the nesting limit of parsers like serde_json.
+pub(crate) const MAX_DEPTH: usize = 100;
+
+impl ErrorMessage {
+    /// Render the chain as compact JSON without depending on serde.
+    ///
+    /// The output has the same shape as serializing the [ErrorMessage] with the `serde` feature and
+    /// is byte-identical to the output of `serde_json::to_string`.
+    /// Causes, that are not [ErrorMessage]s, are written as a frame with their Display message.
+    /// Only the first 100 nested frames are written, the rest of the chain is summarized in a frame like `... 5 more causes`.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Value was \"null\"").context("Failed to parse config");
+    /// # #[cfg(not(feature = "timestamp"))]
+    /// assert_eq!(
+    ///     error.to_json_string(),
+    ///     r#"{"message":"Failed to parse config","cause":{"message":"Value was \"null\"","cause":null}}"#
+    /// );
+    /// ```
+    pub fn to_json_string(&self) -> String {
+        let mut json = String::new();
+        write_json(self, 0, &mut json);
+        json
+    }
+}
+
+/// Writes a frame and all of its causes below the `depth` frames, it is nested in,
+/// without recursing into the causes, so long chains don't overflow the stack
+fn write_json(error: &ErrorMessage, depth: usize, json: &mut String) {
+    let mut current = error;
+    let mut frames = 0;
+    loop {
+        frames += 1;
+        json.push_str("{\"message\":");
+        write_string(&current.message, json);
+        if let Some(id) = current.frame_id() {
+            json.push_str(",\"id\":");
+            write_string(id, json);
+        }
+        if let Some(code) = current.frame_code() {
+            json.push_str(",\"code\":");
+            write_string(code, json);
+        }
+        if !current.details().is_empty() {
+            json.push_str(",\"details\":{");
+            for (index, (key, value)) in current.details().iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                write_string(key, json);
+                json.push(':');
+                write_string(value, json);
+            }
+            json.push('}');
+        }
+        if !current.frame_hints().is_empty() {
+            json.push_str(",\"hints\":[");
+            for (index, hint) in current.frame_hints().iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                write_string(hint, json);
+            }
+            json.push(']');
+        }
+        if current.frame_transient() {
+            json.push_str(",\"transient\":true");
+        }
+        #[cfg(feature = "http")]
+        if let Some(status) = current.frame_status() {
+            let _ = write!(json, ",\"status\":{}", status.as_u16());
+        }
+        #[cfg(feature = "timestamp")]
+        {
+            let _ = write!(json, ",\"timestamp\":{}", current.timestamp_millis());
+        }
+        if !current.branches().is_empty() {
+            json.push_str(",\"branches\":[");
+            for (index, branch) in current.branches().iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                write_json(branch, depth + frames, json);
+            }
+            json.push(']');
+        }
+        json.push_str(",\"cause\":");
+        match &current.cause {
+            Some(Cause::Message(cause)) if depth + frames >= MAX_DEPTH => {
+                write_leaf(&more_causes(cause.depth()), json);
+                break;
+            }
+            Some(Cause::Message(cause)) => current = cause,
+            Some(Cause::Other(cause)) => {
+                write_leaf(&cause.to_string(), json);
+                break;
+            }
+            None => {
+                json.push_str("null");
+                break;
+            }
+        }
+    }
+    for _ in 0..frames {
+        json.push('}');
+    }
+}
+
+/// Writes a frame without a cause, that only has a message
+fn write_leaf(message: &str, json: &mut String) {
+    json.push_str("{\"message\":");
+    write_string(message, json);
+    json.push_str(",\"cause\":null}");
+}
+
+/// Writes a JSON string with the same escapes as serde_json, which leaves non-ASCII characters as they are
+pub(crate) fn write_string(value: &str, json: &mut String) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\u{08}' => json.push_str("\\b"),
+            '\u{0C}' => json.push_str("\\f"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\u{00}'..='\u{1F}' => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
@@ -0,0 +1,73 @@
+//! A per-thread slot for the last [ErrorMessage], for C-style APIs.
+//!
+//! FFI functions usually return a status code and offer a separate function to ask what went wrong.
+//! Store the error with [set] or [StoreErr::store_err] before returning the status code,
+//! and hand it out in the `get_last_error()` function with [take] or [with_last].
+//!
+//! Every thread has its own slot, so errors never leak into calls from other threads.
+//! Once [taken](take), the slot is empty again.
+//!
+//! ```rust
+//! use errors_with_context::ErrorMessage;
+//! use errors_with_context::last_error::{self, StoreErr};
+//!
+//! fn load_config() -> Result<u32, ErrorMessage> {
+//!     ErrorMessage::err("Configuration file is missing")
+//! }
+//!
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn app_load_config() -> i32 {
+//!     match load_config().store_err() {
+//!         Some(_) => 0,
+//!         None => -1,
+//!     }
+//! }
+//!
+//! assert_eq!(app_load_config(), -1);
+//! let error = last_error::take().unwrap();
+//! assert_eq!(error.to_string(), "Configuration file is missing");
+//! assert!(last_error::take().is_none());
+//! ```
+
+use crate::ErrorMessage;
+use std::cell::RefCell;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<ErrorMessage>> = const { RefCell::new(None) };
+}
+
+/// Store the error in the slot of the current thread, replacing the previous one.
+pub fn set(error: ErrorMessage) {
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = Some(error));
+}
+
+/// Remove the error from the slot of the current thread and return it.
+pub fn take() -> Option<ErrorMessage> {
+    LAST_ERROR.with(|slot| slot.borrow_mut().take())
+}
+
+/// Run a closure with the error in the slot of the current thread, without removing it.
+///
+/// The slot can't be modified from within the closure.
+pub fn with_last<R>(f: impl FnOnce(Option<&ErrorMessage>) -> R) -> R {
+    LAST_ERROR.with(|slot| f(slot.borrow().as_ref()))
+}
+
+/// [StoreErr] is implemented for `Result<T, ErrorMessage>`
+pub trait StoreErr<T> {
+    /// Returns the `Ok` value as [Some].
+    /// An `Err` is [set] as last error of the current thread and [None] is returned.
+    fn store_err(self) -> Option<T>;
+}
+
+impl<T> StoreErr<T> for Result<T, ErrorMessage> {
+    fn store_err(self) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(error) => {
+                set(error);
+                None
+            }
+        }
+    }
+}
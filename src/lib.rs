@@ -4,8 +4,8 @@
 //! If the error is application-ending, I just want to pass it up the stack and eventually either print or serialize it.
 //! And that's exactly what this library does, while remaining as small as possible.
 //! 
-//! - 0 required dependencies (1 optional dependency for serialization or errors)
-//! - 0 macros
+//! - 0 required dependencies (every integration with another crate is an optional dependency, see [Features](#features))
+//! - 0 macros (unless you opt into the `macros` feature)
 //! - many optional features, so you only compile what you need
 //!
 //! It implements a single type [ErrorMessage] and a single trait [WithContext], which is implemented for [Result] and [Option].
@@ -248,6 +248,29 @@
 //! | pretty_debug_errors | true               |                                | Enable pretty debug errors                                 |
 //! | boolean_errors      | false              |                                | Allow turning booleans into [ErrorMessages](ErrorMessage)  |
 //! | serde               | false              | dependency: "serde"            | Allow serialization of [ErrorMessages](ErrorMessage)       |
+//! | macros              | false              |                                | Add the [err_context] macro, which records the call site   |
+//! | stats               | false              |                                | Count created errors with the [ErrorStats] registry        |
+//! | valuable            | false              | dependency: "valuable"         | Record [ErrorMessages](ErrorMessage) as structured values  |
+//! | last_error          | false              |                                | Add a per-thread slot for the last error (for FFI)         |
+//! | color               | false              |                                | Render error chains with ANSI colors for terminals         |
+//! | location            | false              |                                | Record the caller [Location] in every new frame            |
+//! | backtrace           | false              |                                | Capture a backtrace, when the innermost error is created   |
+//! | timestamp           | false              |                                | Record the creation time of every frame                    |
+//! | thread_info         | false              |                                | Record the thread, that created the innermost error        |
+//! | log                 | false              | dependency: "log"              | Log error chains with the [log](https://crates.io/crates/log) crate |
+//! | tracing             | false              | dependency: "tracing"          | Record error chains as [tracing](https://crates.io/crates/tracing) events |
+//! | spantrace           | false              | dependency: "tracing-error"    | Capture the current spans, when the innermost error is created |
+//! | sentry              | false              | dependency: "sentry"           | Convert error chains into [Sentry](https://crates.io/crates/sentry) events |
+//! | miette              | false              | dependency: "miette"           | Implement [Diagnostic](https://docs.rs/miette/latest/miette/trait.Diagnostic.html) for [ErrorMessage] |
+//! | std_conversions     | false              |                                | Convert common standard library errors with `?`            |
+//! | anyhow              | false              | dependency: "anyhow"           | Convert between [ErrorMessage] and [anyhow](https://crates.io/crates/anyhow) errors |
+//! | eyre                | false              | dependency: "eyre"             | Convert between [ErrorMessage] and [eyre](https://crates.io/crates/eyre) reports |
+//! | display_errors      | false              |                                | Add context to errors, that only implement Display         |
+//! | crossbeam           | false              | dependency: "crossbeam-channel" | Add context to send errors of crossbeam channels          |
+//! | process             | false              |                                | Turn processes, that exited unsuccessfully, into errors    |
+//! | axum                | false              | dependency: "axum"             | Turn errors into HTTP responses of axum, enables `send`    |
+//! | actix               | false              | dependency: "actix-web"        | Turn errors into HTTP responses of actix-web               |
+//! | http                | false              | dependency: "http"             | Attach HTTP status codes to errors                         |
 //!
 //! <br><br>
 //!
@@ -281,6 +304,8 @@
 //!
 //! Still readable, but not as nice for humans.
 //!
+//! With this feature, the alternate flag `{:#?}` (as used by `dbg!`) still writes this structure with one field per line.
+//!
 //! <br>
 //!
 //! ## Feature: `boolean_errors`
@@ -295,6 +320,8 @@
 //!
 //! This allows for cool code like this:
 //! ```rust
+//! # #[cfg(feature = "boolean_errors")]
+//! # mod example {
 //! # use std::path::Path;
 //! use errors_with_context::{BooleanErrors, ErrorMessage};
 //! # fn func() -> Result<(), ErrorMessage> {
@@ -303,13 +330,20 @@
 //!     .error_if_false("Expected file to exist!")?;
 //! #   Ok(())
 //! # }
-//! # fn main() {
+//! # pub fn main() {
 //! #     let  string = func().unwrap_err().to_string();
 //! #     assert_eq!(string, "Expected file to exist!");
 //! # }
+//! # }
+//! # fn main() {
+//! #     #[cfg(feature = "boolean_errors")]
+//! #     example::main();
+//! # }
 //! ```
 //! or with more dynamic context:
 //! ```rust
+//! # #[cfg(feature = "boolean_errors")]
+//! # mod example {
 //! # use std::path::Path;
 //! use errors_with_context::prelude::*;
 //! # fn func() -> Result<(), ErrorMessage> {
@@ -318,10 +352,15 @@
 //!     .error_dyn_if_false(|| format!("Expected file '{}' to exist!", path.display()))?;
 //! #   Ok(())
 //! # }
-//! # fn main() {
+//! # pub fn main() {
 //! #     let  string = func().unwrap_err().to_string();
 //! #     assert_eq!(string, "Expected file 'test.file' to exist!");
 //! # }
+//! # }
+//! # fn main() {
+//! #     #[cfg(feature = "boolean_errors")]
+//! #     example::main();
+//! # }
 //! ```
 //!
 //! Very useful, when doing lots of checks that aren't immediately errors.
@@ -335,6 +374,7 @@
 //!
 //! This adds the requirements for all causes to be [Send].
 //! This is useful for sending errors between threads.
+//! It also allows converting an [ErrorMessage] into an [io::Error](std::io::Error), which is recovered by [from_io_roundtrip](ErrorMessage::from_io_roundtrip).
 //!
 //! Example:
 //! ```rust
@@ -362,8 +402,12 @@
 //! (disabled by default)
 //!
 //! This feature enables serialization of [ErrorMessage]s with [serde](https://crates.io/crates/serde).
+//! Only the first 100 nested frames are serialized, the rest of the chain is summarized in a frame like `... 5 more causes`,
+//! so very deep chains can't overflow the stack.
 //!
 //! ```
+//! # #[cfg(all(feature = "serde", not(feature = "timestamp")))]
+//! # {
 //! # use std::convert::Infallible;
 //! # use std::io;
 //! # use errors_with_context::prelude::*;
@@ -388,6 +432,7 @@
 //! #     }
 //! #   }
 //! # }"#)
+//! # }
 //! ```
 //! results in
 //! ```json
@@ -405,29 +450,658 @@
 //!   }
 //! }
 //! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `macros`
+//!
+//! (disabled by default)
+//!
+//! This adds the [err_context] macro, a thin wrapper around [with_dyn_err_context](WithContext::with_dyn_err_context),
+//! which formats the message and records the [Location] (file, line, column and module) of the call site in the new frame.
+//!
+//! ```rust
+//! # #[cfg(feature = "macros")]
+//! # {
+//! # use std::io;
+//! use errors_with_context::{err_context, ErrorMessage};
+//! # fn read_file(path: &str) -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+//! let path = "config.json";
+//! let result: Result<String, ErrorMessage> = err_context!(read_file(path), "Failed to read file '{path}'");
+//! let location = result.unwrap_err().location().copied();
+//! # assert_eq!(location.unwrap().file(), file!());
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `stats`
+//!
+//! (disabled by default)
+//!
+//! This adds the [ErrorStats] registry, which counts how often each kind of error is created.
+//! Once installed, every newly created chain is counted once, grouped either by its message or by a fingerprint (see [StatsKey]).
+//! The number of distinct keys is bounded, the least recently seen keys are dropped first.
+//!
+//! ```rust
+//! # #[cfg(feature = "stats")]
+//! # {
+//! use errors_with_context::{ErrorMessage, ErrorStats};
+//! let stats = ErrorStats::install();
+//! let _ = ErrorMessage::new("Failed to connect to database");
+//! for (key, count) in stats.snapshot() {
+//!     println!("{count:>6} {key}");
+//! }
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `valuable`
+//!
+//! (disabled by default)
+//!
+//! This implements [valuable](https://crates.io/crates/valuable)'s `Valuable` and `Structable` for [ErrorMessage],
+//! so structured loggers see the fields `message`, `code` (the error code of the chain), `details` (the details of the top message)
+//! and `causes` (the list of every frame below the top message)
+//! instead of a flattened string.
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `last_error`
+//!
+//! (disabled by default)
+//!
+//! This adds the module [last_error] with a per-thread slot for the last [ErrorMessage],
+//! for C-style APIs that return a status code and offer a separate `get_last_error()` function.
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `color`
+//!
+//! (disabled by default)
+//!
+//! This adds `ErrorMessage::display_colored`, which renders the chain with plain ANSI escape codes:
+//! The outermost message is bold red, `caused by:` is dimmed and the root cause is yellow.
+//! ```rust
+//! # #[cfg(feature = "color")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! let error = ErrorMessage::new("I/O Error").context("Failed to read file");
+//! eprintln!("{}", error.display_colored());
+//! # }
+//! ```
+//! The normal Display output and the serde output stay free of colors.
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `location`
+//!
+//! (disabled by default)
+//!
+//! With this feature, the constructors of [ErrorMessage] and the methods of [WithContext] are `#[track_caller]`.
+//! Every new frame records the [Location] (file, line and column) of the code, that created it.
+//! It is shown by [display_verbosity](ErrorMessage::display_verbosity) at level 2 and, with the `color` feature,
+//! as a dimmed suffix like `(at src/config.rs:42:10)` in [display_colored](ErrorMessage::display_colored).
+//! The normal Display, Debug and serde output stays the same.
+//!
+//! ```rust
+//! # #[cfg(feature = "location")]
+//! # {
+//! # use std::io;
+//! use errors_with_context::prelude::*;
+//! # fn read_file() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+//! let error = read_file().with_err_context("Failed to read file").unwrap_err();
+//! let location = error.location().unwrap();
+//! assert_eq!(location.file(), file!());
+//! # }
+//! ```
+//!
+//! Without the feature, nothing is recorded and the API doesn't change.
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `backtrace`
+//!
+//! (disabled by default)
+//!
+//! With this feature, a [Backtrace](std::backtrace::Backtrace) is captured, whenever the innermost [ErrorMessage] of a chain is created.
+//! Just like [Backtrace::capture](std::backtrace::Backtrace::capture), this honors the `RUST_LIB_BACKTRACE` and `RUST_BACKTRACE` environment variables,
+//! so nothing is captured or allocated, unless they enable it.
+//! The backtrace is available with [backtrace](ErrorMessage::backtrace) and shown after the chain by [display_verbosity](ErrorMessage::display_verbosity) at level 2.
+//!
+//! ```rust
+//! # #[cfg(feature = "backtrace")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+//! eprintln!("{}", error.display_verbosity(2));
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `timestamp`
+//!
+//! (disabled by default)
+//!
+//! With this feature, every frame records the time it was created at, which is available with [created_at](ErrorMessage::created_at).
+//! [display_timestamps](ErrorMessage::display_timestamps) writes it behind every message as an RFC 3339 timestamp in UTC
+//! and the serde output contains it as the `timestamp` field in milliseconds since the Unix epoch.
+//! Without the feature, neither the Display nor the serde output change.
+//!
+//! ```rust
+//! # #[cfg(feature = "timestamp")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+//! eprintln!("{}", error.display_timestamps());
+//! // Failed to sync (2024-05-01T12:30:00.124Z)
+//! //   caused by: Connection refused (2024-05-01T12:30:00.123Z)
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `thread_info`
+//!
+//! (disabled by default)
+//!
+//! With this feature, the innermost [ErrorMessage] of a chain records the name and the id of the thread, that created it.
+//! They are available with [thread_name](ErrorMessage::thread_name) and [thread_id](ErrorMessage::thread_id) from every frame of the chain
+//! and [display_thread](ErrorMessage::display_thread) writes them like `(on thread "worker-3")`.
+//!
+//! ```rust
+//! # #[cfg(feature = "thread_info")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+//! eprintln!("{}", error.display_thread());
+//! // Failed to sync
+//! //   caused by: Connection refused (on thread "main")
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `log`
+//!
+//! (disabled by default)
+//!
+//! This adds [LogWithContext], which adds context just like [WithContext] and writes the new chain to the [log](https://crates.io/crates/log) crate,
+//! and [ErrorMessage::log] for errors, that were already built.
+//! The chain is logged on a single line with the target `errors_with_context`.
+//! For errors, that are not fatal, [warn_err_context](LogWithContext::warn_err_context) logs the chain at the warn level and returns [None] instead.
+//!
+//! ```rust
+//! # #[cfg(feature = "log")]
+//! # {
+//! # use std::io;
+//! use errors_with_context::prelude::*;
+//! use log::Level;
+//! # fn read_config() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+//! let config = read_config().log_err_context(Level::Warn, "Failed to read config");
+//! // logs "Failed to read config: Kind(NotFound)"
+//! let cache = read_config().warn_err_context("Failed to read cache");
+//! // logs "Failed to read cache: Kind(NotFound)" and continues with None
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `tracing`
+//!
+//! (disabled by default)
+//!
+//! This adds [TraceWithContext], which adds context just like [WithContext] and records the new chain as a [tracing](https://crates.io/crates/tracing) event,
+//! and [ErrorMessage::record] for errors, that were already built.
+//! The event is recorded at the error level inside the current span and contains the fields `error.chain`, `error.root` and `error.depth`.
+//!
+//! ```rust
+//! # #[cfg(feature = "tracing")]
+//! # {
+//! # use std::io;
+//! use errors_with_context::prelude::*;
+//! # fn read_config() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+//! let config = read_config().trace_err_context("Failed to read config");
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `spantrace`
+//!
+//! (disabled by default)
+//!
+//! With this feature, a [SpanTrace](tracing_error::SpanTrace) of the current [tracing](https://crates.io/crates/tracing) spans is captured,
+//! whenever the innermost [ErrorMessage] of a chain is created.
+//! This only works, if the current subscriber contains an [ErrorLayer](tracing_error::ErrorLayer) from [tracing-error](https://crates.io/crates/tracing-error).
+//! The span trace is available with [span_trace](ErrorMessage::span_trace)
+//! and shown after the chain by [display_verbosity](ErrorMessage::display_verbosity) at level 2.
+//!
+//! ```rust
+//! # #[cfg(feature = "spantrace")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! let error = ErrorMessage::new("Configuration file is missing");
+//! if let Some(span_trace) = error.span_trace() {
+//!     eprintln!("{span_trace}");
+//! }
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `sentry`
+//!
+//! (disabled by default)
+//!
+//! This adds [to_sentry_event](ErrorMessage::to_sentry_event), which converts a chain into a Sentry event with one exception per frame,
+//! so the whole chain shows up in the issue instead of a single message,
+//! and [capture](ErrorMessage::capture), which sends the event to the bound Sentry client.
+//!
+//! ```rust
+//! # #[cfg(feature = "sentry")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+//! error.capture();
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `miette`
+//!
+//! (disabled by default)
+//!
+//! This implements [miette](https://crates.io/crates/miette)'s `Diagnostic` for [ErrorMessage], so it can be rendered by miette's report handlers.
+//! The [error code](ErrorMessage::error_code), the [id](ErrorMessage::id) or else the [exit code](ErrorMessage::exit_code) becomes the code of the diagnostic
+//! and the [hints](ErrorMessage::hints) become its help text.
+//!
+//! ```rust
+//! # #[cfg(feature = "miette")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! use miette::{Diagnostic, NarratableReportHandler};
+//! let error = ErrorMessage::new("Configuration file is missing").with_id("CFG001").with_hint("run `myapp init` to create it");
+//! let mut report = String::new();
+//! NarratableReportHandler::new().render_report(&mut report, &error).unwrap();
+//! # assert!(report.contains("diagnostic code: CFG001"));
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `std_conversions`
+//!
+//! (disabled by default)
+//!
+//! This implements [From] for [ErrorMessage] for a few common errors of the standard library,
+//! so they can be thrown with `?` in throwaway code without adding context:
+//! `io::Error`, `ParseIntError`, `ParseFloatError`, `TryFromIntError`, `Utf8Error`, `FromUtf8Error`, `SystemTimeError` and `fmt::Error`.
+//! They are converted like with [from_error](ErrorMessage::from_error).
+//!
+//! Adding context is still recommended, because "invalid digit found in string" doesn't tell, which input was wrong.
+//!
+//! ```rust
+//! # #[cfg(feature = "std_conversions")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! fn read_port(path: &str) -> Result<u16, ErrorMessage> {
+//!     let port = std::fs::read_to_string(path)?;
+//!     Ok(port.trim().parse()?)
+//! }
+//! # assert!(read_port("missing.file").unwrap_err().to_string().contains("caused by: Os"));
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `anyhow`
+//!
+//! (disabled by default)
+//!
+//! This adds [into_anyhow](ErrorMessage::into_anyhow), which converts a chain into an [anyhow](https://crates.io/crates/anyhow) error,
+//! that has one context layer for every frame, for passing errors to code, that is based on anyhow.
+//!
+//! The other way around, [AnyhowWithContext] adds context to a `Result<T, anyhow::Error>` just like [WithContext] does it for other errors,
+//! and [from_anyhow](ErrorMessage::from_anyhow) converts an [anyhow::Error] without adding context.
+//! Every context layer of the [anyhow::Error] becomes a frame of the chain.
+//!
+//! ```rust
+//! # #[cfg(feature = "anyhow")]
+//! # {
+//! use errors_with_context::ErrorMessage;
+//! let error = ErrorMessage::new("Connection refused").context("Failed to sync").into_anyhow();
+//! assert_eq!(format!("{error:#}"), "Failed to sync: Connection refused");
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `eyre`
+//!
+//! (disabled by default)
+//!
+//! This works just like the `anyhow` feature, but for [eyre](https://crates.io/crates/eyre):
+//! [EyreWithContext] adds context to a `Result<T, eyre::Report>`, [from_eyre](ErrorMessage::from_eyre) converts an [eyre::Report] into a chain
+//! and [into_eyre](ErrorMessage::into_eyre) converts a chain into an [eyre::Report], that has one layer for every frame.
+//!
+//! ```rust
+//! # #[cfg(feature = "eyre")]
+//! # {
+//! use errors_with_context::prelude::*;
+//! use eyre::eyre;
+//! let report: eyre::Result<()> = Err(eyre!("Connection refused"));
+//! let error = report.with_err_context("Failed to sync").unwrap_err();
+//! let report = error.into_eyre();
+//! # assert_eq!(report.chain().count(), 3);
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `display_errors`
+//!
+//! (disabled by default)
+//!
+//! This adds [WithDisplayContext] for errors, that implement [Display](std::fmt::Display), but not [Error](std::error::Error),
+//! like the errors of some FFI wrappers.
+//! Its methods have their own names, because implementing [WithContext] for all [Display](std::fmt::Display) errors would conflict with the implementation for all errors.
+//!
+//! ```rust
+//! # #[cfg(feature = "display_errors")]
+//! # {
+//! use errors_with_context::prelude::*;
+//! # struct DeviceError;
+//! # impl std::fmt::Display for DeviceError {
+//! #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str("Device is busy") }
+//! # }
+//! # fn open_device() -> Result<(), DeviceError> { Err(DeviceError) }
+//! let device = open_device().with_display_err_context("Failed to open the camera");
+//! # assert_eq!(device.unwrap_err().to_string(), "Failed to open the camera\n  caused by: Device is busy");
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `crossbeam`
+//!
+//! (disabled by default)
+//!
+//! This implements [SendErrors] for the send errors of [crossbeam-channel](https://crates.io/crates/crossbeam-channel),
+//! just like it is implemented for the channels of the standard library.
+//!
+//! ```rust
+//! # #[cfg(feature = "crossbeam")]
+//! # {
+//! use errors_with_context::prelude::*;
+//! let (sender, receiver) = crossbeam_channel::bounded(0);
+//! drop(receiver);
+//! let result = sender.send("backup").with_send_err_context("Failed to queue the job");
+//! # assert_eq!(result.unwrap_err().to_string(), "Failed to queue the job\n  caused by: channel closed");
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `process`
+//!
+//! (disabled by default)
+//!
+//! This adds [ProcessErrors] for [ExitStatus](std::process::ExitStatus) and [Output](std::process::Output),
+//! which turns a process, that exited unsuccessfully, into an [ErrorMessage] with its exit code and the end of its stderr,
+//! and [run_with_context], which runs a command and returns its stdout, like the `run` function of the example above.
+//!
+//! ```rust
+//! # #[cfg(feature = "process")]
+//! # {
+//! use errors_with_context::run_with_context;
+//! let outputs = run_with_context("swaymsg", ["-t", "get_outputs"]);
+//! # }
+//! ```
+//! returns this error, if the command can't be found
+//! ```text
+//! Failed to run command 'swaymsg'
+//!   caused by: Os { code: 2, kind: NotFound, message: "No such file or directory" }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `axum`
+//!
+//! (disabled by default)
+//!
+//! This implements `IntoResponse` of [axum](https://crates.io/crates/axum) for [ErrorMessage],
+//! so handlers can return `Result<_, ErrorMessage>` directly.
+//! The response has the [status](ErrorMessage::status) of the error, or else `500 Internal Server Error`,
+//! and a JSON body with the outermost message and the whole chain.
+//! The chain is recorded with [record](ErrorMessage::record), if the `tracing` feature is enabled,
+//! and can be left out of the responses with [hide_error_internals], for example in production.
+//!
+//! This feature enables the `send` and `http` features.
+//! Features are enabled for the whole dependency graph, so with `send`, every cause has to be `Send`
+//! in the bounds of `with_err_context` and the other methods for all crates, that use errors_with_context.
+//!
+//! ```rust
+//! # #[cfg(feature = "axum")]
+//! # {
+//! use errors_with_context::prelude::*;
+//! async fn load_user() -> Result<String, ErrorMessage> {
+//!     ErrorMessage::err("Connection refused").with_err_context("Failed to load the user")
+//! }
+//! # }
+//! ```
+//! responds with
+//! ```json
+//! {"error":"Failed to load the user","chain":["Failed to load the user","Connection refused"]}
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `actix`
+//!
+//! (disabled by default)
+//!
+//! This implements `ResponseError` of [actix-web](https://crates.io/crates/actix-web) for [ErrorMessage],
+//! so handlers returning `Result<HttpResponse, ErrorMessage>` can use `?` directly.
+//! Just like with the `axum` feature, the response has the status of the error or `500 Internal Server Error` and a JSON body
+//! with the outermost message and the whole chain, which can be left out with [hide_error_internals].
+//!
+//! This feature enables the `http` feature.
+//!
+//! ```rust
+//! # #[cfg(feature = "actix")]
+//! # {
+//! use actix_web::HttpResponse;
+//! use errors_with_context::prelude::*;
+//! async fn load_user() -> Result<HttpResponse, ErrorMessage> {
+//!     let name: String = ErrorMessage::err("Connection refused").with_err_context("Failed to load the user")?;
+//!     Ok(HttpResponse::Ok().body(name))
+//! }
+//! # }
+//! ```
+//!
+//! <br>
+//!
+//!
+//! ## Feature: `http`
+//!
+//! (disabled by default)
+//!
+//! This adds [with_status](ErrorMessage::with_status) and [status](ErrorMessage::status)
+//! for attaching the HTTP status of the [http](https://crates.io/crates/http) crate to an error, independent of the web framework.
+//! Just like the [exit code](ErrorMessage::exit_code), it is kept when adding more context and the outermost status wins.
+//! The `axum` and `actix` features respond with it, and serializing the error writes it as the numeric field `status` of its frame.
+//!
+//! ```rust
+//! # #[cfg(feature = "http")]
+//! # {
+//! use http::StatusCode;
+//! use errors_with_context::prelude::*;
+//! let result: Result<(), _> = ErrorMessage::err("No user with id 42")
+//!     .map_err(|error| error.with_status(StatusCode::NOT_FOUND))
+//!     .with_err_context("Failed to load the user");
+//! assert_eq!(result.unwrap_err().status(), Some(StatusCode::NOT_FOUND));
+//! # }
+//! ```
 
 #![warn(missing_docs, rustdoc::broken_intra_doc_links)]
 
+use std::borrow::Cow;
+
+#[cfg(feature = "actix")]
+mod actix;
+#[cfg(feature = "anyhow")]
+mod anyhow;
+#[cfg(feature = "axum")]
+mod axum;
+mod boxed;
+mod chain;
+mod channel;
+#[cfg(feature = "color")]
+mod color;
+mod context_error;
+#[cfg(feature = "std_conversions")]
+mod conversions;
+mod display;
+mod ensure;
 mod error_message;
+mod flatten;
+#[cfg(feature = "eyre")]
+mod eyre;
+mod hook;
+#[cfg(feature = "http")]
+mod http;
+mod io;
+mod json;
+#[cfg(feature = "last_error")]
+pub mod last_error;
+#[cfg(any(feature = "macros", feature = "location"))]
+mod location;
+mod lock;
+#[cfg(feature = "macros")]
+mod macros;
+mod markdown;
+#[cfg(feature = "miette")]
+mod miette;
 mod option;
+mod os_string;
+mod panic;
+mod poll;
+mod reference;
+mod report;
 mod result;
 #[cfg(feature = "serde")]
 mod serde;
+#[cfg(feature = "stats")]
+mod stats;
+mod string;
+mod stringify;
+mod tap;
+#[cfg(feature = "timestamp")]
+mod timestamp;
+mod tree;
+#[cfg(feature = "valuable")]
+mod valuable;
+#[cfg(any(feature = "axum", feature = "actix"))]
+mod web;
+#[cfg(feature = "sentry")]
+mod sentry;
 #[cfg(test)]
 mod tests;
 
+pub use crate::boxed::BoxedWithContext;
+pub use crate::chain::Chain;
+pub use crate::channel::SendErrors;
+pub use crate::context_error::{ContextError, TypedWithContext};
+pub use crate::ensure::{ensure, ensure_dyn, ensure_dyn_eq, ensure_dyn_ne, ensure_eq, ensure_ne};
 pub use crate::error_message::ErrorMessage;
+pub use crate::flatten::FlattenWithContext;
+pub use crate::hook::set_error_hook;
+#[cfg(feature = "send")]
+pub use crate::io::IoErrorMessage;
+pub use crate::lock::LockErrors;
+pub use crate::os_string::OsStringErrors;
+pub use crate::panic::{
+    catch_unwind_with_context, install_panic_hook, install_panic_hook_chained, restore_default_panic_hook, try_catch_unwind_with_context,
+    JoinErrors, TryJoinErrors,
+};
+pub use crate::poll::PollWithContext;
+pub use crate::reference::WithContextRef;
+pub use crate::report::Report;
+pub use crate::string::StringWithContext;
+pub use crate::stringify::StringifyWithContext;
+pub use crate::tap::TapWithContext;
+pub use crate::tree::ErrorNode;
+#[cfg(any(feature = "axum", feature = "actix"))]
+pub use crate::web::hide_error_internals;
+#[cfg(any(feature = "macros", feature = "location"))]
+pub use crate::location::Location;
+#[cfg(feature = "stats")]
+pub use crate::stats::{ErrorStats, StatsKey};
 
 /// Group all traits of this crate
 pub mod prelude {
+    #[cfg(feature = "anyhow")]
+    pub use super::anyhow::AnyhowWithContext;
+    #[cfg(feature = "eyre")]
+    pub use super::eyre::EyreWithContext;
     #[cfg(feature = "boolean_errors")]
     pub use super::boolean::BooleanErrors;
+    pub use super::boxed::BoxedWithContext;
+    #[cfg(feature = "display_errors")]
+    pub use super::display_errors::WithDisplayContext;
+    #[cfg(feature = "process")]
+    pub use super::process::ProcessErrors;
+    pub use super::channel::SendErrors;
+    pub use super::context_error::TypedWithContext;
     pub use super::error_message::ErrorMessage;
+    pub use super::flatten::FlattenWithContext;
+    pub use super::lock::LockErrors;
+    pub use super::os_string::OsStringErrors;
+    pub use super::panic::{JoinErrors, TryJoinErrors};
+    pub use super::poll::PollWithContext;
+    pub use super::string::StringWithContext;
+    pub use super::stringify::StringifyWithContext;
+    pub use super::tap::TapWithContext;
+    #[cfg(feature = "log")]
+    pub use super::log::LogWithContext;
+    #[cfg(feature = "tracing")]
+    pub use super::tracing::TraceWithContext;
     pub use super::WithContext;
+    pub use super::WithContextRef;
 }
 
 /// [WithContext] is implemented for [Result] and [Option]
 /// see [with_err_context](WithContext::with_err_context) and [with_dyn_err_context](WithContext::with_dyn_err_context) for more details
+///
+/// Boxed errors like `Box<dyn Error>` don't implement [Error](std::error::Error) themselves, [BoxedWithContext] covers them instead.
+/// [StringWithContext] does the same for `Result<T, String>` and `Result<T, &str>`.
+/// Errors, that borrow data or aren't [Send], can be converted into a string frame with [StringifyWithContext].
 pub trait WithContext<T, E> {
     /// Convert an [`Option<T>`] or [`Result<T,_>`] into [`Result<T, ErrorMessage>`]
     ///
@@ -462,8 +1136,25 @@ pub trait WithContext<T, E> {
     /// ```
     fn with_err_context(self, reason: impl ToString) -> Result<T, ErrorMessage>;
 
+    /// Like [with_err_context](WithContext::with_err_context), but a `&'static str` is stored as it is,
+    /// instead of copying it into a new [String], which avoids the allocation for contexts, that are string literals.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::WithContext;
+    /// fn produce_err() -> Result<(), io::Error> { Err(io::ErrorKind::UnexpectedEof.into())}
+    /// # let e =
+    /// produce_err()
+    ///     .with_err_context_static("Something went wrong in function 'produce_err'");
+    /// # assert_eq!(e.unwrap_err().to_string(), "Something went wrong in function 'produce_err'\n  caused by: Kind(UnexpectedEof)");
+    /// ```
+    fn with_err_context_static(self, reason: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage>;
+
     /// Convert an [`Option<T>`] or [`Result<T,_>`] into [`Result<T, ErrorMessage>`]
     ///
+    /// The closure is only called in the error case.
+    /// It can return a [String], a `&'static str` or a [`Cow<'static, str>`](Cow), which is stored without copying it.
+    ///
     /// Example usage for [`Option<T>`]
     /// ```rust
     /// # use std::io;
@@ -501,10 +1192,102 @@ pub trait WithContext<T, E> {
     /// Something went wrong in function 'produce_err'
     ///  caused by: Kind(UnexpectedEof)
     /// ```
-    fn with_dyn_err_context(self, reason: impl FnOnce() -> String) -> Result<T, ErrorMessage>;
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, reason: impl FnOnce() -> S) -> Result<T, ErrorMessage>;
+
+    /// Like [with_err_context](WithContext::with_err_context), but also attaches an identifier to the new frame.
+    /// See [ErrorMessage::with_id].
+    ///
+    /// ```rust
+    /// use errors_with_context::WithContext;
+    /// fn produce_none() -> Option<()> { None }
+    /// # let e =
+    /// produce_none()
+    ///     .with_err_context_id("Configuration value not found", "CFG002");
+    /// # assert_eq!(e.unwrap_err().to_string(), "[CFG002] Configuration value not found");
+    /// ```
+    /// prints
+    /// ```text
+    /// [CFG002] Configuration value not found
+    /// ```
+    fn with_err_context_id(self, reason: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage>;
+
+    /// Like [with_err_context](WithContext::with_err_context), but also attaches an error code to the new frame.
+    /// See [ErrorMessage::with_code].
+    ///
+    /// ```rust
+    /// use errors_with_context::WithContext;
+    /// fn produce_none() -> Option<()> { None }
+    /// # let e =
+    /// produce_none()
+    ///     .with_err_context_code("E1042", "Quota exceeded");
+    /// # assert_eq!(e.unwrap_err().to_string(), "[E1042] Quota exceeded");
+    /// ```
+    /// prints
+    /// ```text
+    /// [E1042] Quota exceeded
+    /// ```
+    fn with_err_context_code(self, code: impl ToString, reason: impl ToString) -> Result<T, ErrorMessage>;
+
+    /// Like [with_err_context](WithContext::with_err_context), but also attaches the key/value pairs as details to the new frame.
+    /// See [ErrorMessage::with_detail].
+    ///
+    /// ```rust
+    /// use errors_with_context::WithContext;
+    /// fn produce_none() -> Option<()> { None }
+    /// # let e =
+    /// produce_none()
+    ///     .with_err_context_kv("Failed to open file", [("path", "/etc/app.toml"), ("mode", "rw")]);
+    /// # assert_eq!(e.unwrap_err().to_string(), "Failed to open file\n  path: /etc/app.toml\n  mode: rw");
+    /// ```
+    /// prints
+    /// ```text
+    /// Failed to open file
+    ///   path: /etc/app.toml
+    ///   mode: rw
+    /// ```
+    fn with_err_context_kv<K: ToString, V: ToString>(self, reason: impl ToString, details: impl IntoIterator<Item = (K, V)>) -> Result<T, ErrorMessage>;
+
+    /// Like [with_err_context_kv](WithContext::with_err_context_kv), but both closures are only called in the error case,
+    /// so values, that are expensive to format, don't cost anything on success.
+    ///
+    /// ```rust
+    /// # use std::path::Path;
+    /// use errors_with_context::WithContext;
+    /// fn produce_none() -> Option<()> { None }
+    /// let path = Path::new("/etc/app.toml");
+    /// # let e =
+    /// produce_none()
+    ///     .with_dyn_err_context_kv(|| "Failed to open file", || [("path", path.display().to_string()), ("mode", "rw".to_string())]);
+    /// # assert_eq!(e.unwrap_err().to_string(), "Failed to open file\n  path: /etc/app.toml\n  mode: rw");
+    /// ```
+    fn with_dyn_err_context_kv<S: Into<Cow<'static, str>>, I: IntoIterator<Item = (K, V)>, K: ToString, V: ToString>(
+        self,
+        reason: impl FnOnce() -> S,
+        details: impl FnOnce() -> I,
+    ) -> Result<T, ErrorMessage>;
 }
 
 #[cfg(feature = "boolean_errors")]
 mod boolean;
 #[cfg(feature = "boolean_errors")]
 pub use boolean::BooleanErrors;
+#[cfg(feature = "log")]
+mod log;
+#[cfg(feature = "log")]
+pub use crate::log::LogWithContext;
+#[cfg(feature = "tracing")]
+mod tracing;
+#[cfg(feature = "tracing")]
+pub use crate::tracing::TraceWithContext;
+#[cfg(feature = "anyhow")]
+pub use crate::anyhow::AnyhowWithContext;
+#[cfg(feature = "eyre")]
+pub use crate::eyre::EyreWithContext;
+#[cfg(feature = "display_errors")]
+mod display_errors;
+#[cfg(feature = "display_errors")]
+pub use crate::display_errors::WithDisplayContext;
+#[cfg(feature = "process")]
+mod process;
+#[cfg(feature = "process")]
+pub use crate::process::{run_with_context, ProcessErrors};
@@ -0,0 +1,58 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// A position in the source code, where a frame of an [ErrorMessage](crate::ErrorMessage) was created.
+///
+/// ```rust
+/// use errors_with_context::Location;
+/// let location = Location::new("src/main.rs", 42, 9, Some("my_app::config"));
+/// assert_eq!(location.to_string(), "src/main.rs:42:9");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    file: &'static str,
+    line: u32,
+    column: u32,
+    module_path: Option<&'static str>,
+}
+
+impl Location {
+    /// Create a new [Location].
+    /// Usually filled by the [file!], [line!], [column!] and [module_path!] macros.
+    pub const fn new(file: &'static str, line: u32, column: u32, module_path: Option<&'static str>) -> Location {
+        Location { file, line, column, module_path }
+    }
+
+    /// The path of the source file
+    pub fn file(&self) -> &'static str {
+        self.file
+    }
+
+    /// The line in the source file
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The column in the source file
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// The path of the module, if it was recorded
+    pub fn module_path(&self) -> Option<&'static str> {
+        self.module_path
+    }
+}
+
+/// Records the location without a module path, which [std::panic::Location] doesn't know
+impl From<&'static std::panic::Location<'static>> for Location {
+    fn from(location: &'static std::panic::Location<'static>) -> Self {
+        Location::new(location.file(), location.line(), location.column(), None)
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+use std::sync::{LockResult, TryLockError, TryLockResult};
+
+/// This trait adds context to the results of locking a [Mutex](std::sync::Mutex) or an [RwLock](std::sync::RwLock).
+///
+/// A [PoisonError](std::sync::PoisonError) contains the guard, so it borrows the lock and can't become the cause of an [ErrorMessage].
+/// The guard is dropped instead and the innermost frame of the chain says `lock poisoned`,
+/// or `lock would block`, if [try_lock](std::sync::Mutex::try_lock) didn't get the lock.
+///
+/// ```rust
+/// # use std::sync::Mutex;
+/// use errors_with_context::prelude::*;
+/// # fn func() -> Result<(), ErrorMessage> {
+/// let state = Mutex::new(Vec::new());
+/// state.lock().with_lock_err_context("Failed to lock the state")?.push(1);
+/// #   Ok(())
+/// # }
+/// # func().unwrap();
+/// ```
+pub trait LockErrors<G> {
+    /// Return the guard or an [ErrorMessage] with the provided context, if the lock is poisoned or would block.
+    fn with_lock_err_context(self, context: impl ToString) -> Result<G, ErrorMessage>;
+
+    /// Like [with_lock_err_context](LockErrors::with_lock_err_context), but the context is only built in case of an error.
+    fn with_dyn_lock_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<G, ErrorMessage>;
+}
+
+impl<G> LockErrors<G> for LockResult<G> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_lock_err_context(self, context: impl ToString) -> Result<G, ErrorMessage> {
+        match self {
+            Ok(guard) => Ok(guard),
+            Err(_) => Err(ErrorMessage::new_static("lock poisoned").context(context)),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_lock_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<G, ErrorMessage> {
+        match self {
+            Ok(guard) => Ok(guard),
+            Err(_) => Err(ErrorMessage::new_static("lock poisoned").context_static(context())),
+        }
+    }
+}
+
+impl<G> LockErrors<G> for TryLockResult<G> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_lock_err_context(self, context: impl ToString) -> Result<G, ErrorMessage> {
+        match self {
+            Ok(guard) => Ok(guard),
+            Err(error) => Err(ErrorMessage::new_static(try_lock_message(&error)).context(context)),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_lock_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<G, ErrorMessage> {
+        match self {
+            Ok(guard) => Ok(guard),
+            Err(error) => Err(ErrorMessage::new_static(try_lock_message(&error)).context_static(context())),
+        }
+    }
+}
+
+fn try_lock_message<G>(error: &TryLockError<G>) -> &'static str {
+    match error {
+        TryLockError::Poisoned(_) => "lock poisoned",
+        TryLockError::WouldBlock => "lock would block",
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{ErrorMessage, WithContext};
+use log::Level;
+use std::borrow::Cow;
+
+/// The target of the records written by this crate
+const TARGET: &str = "errors_with_context";
+
+/// [LogWithContext] is implemented for everything, that implements [WithContext].
+/// It adds the context like [with_err_context](WithContext::with_err_context) and writes the new chain to the [log] crate.
+pub trait LogWithContext<T, E> {
+    /// Like [with_err_context](WithContext::with_err_context), but in case of an error,
+    /// the whole chain is also logged at the given [Level] on a single line, see [ErrorMessage::log].
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// use log::Level;
+    /// fn read_config() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+    ///
+    /// let result = read_config().log_err_context(Level::Warn, "Failed to read config");
+    /// // logs "Failed to read config: Kind(NotFound)"
+    /// # assert_eq!(result.unwrap_err().to_string(), "Failed to read config\n  caused by: Kind(NotFound)");
+    /// ```
+    fn log_err_context(self, level: Level, context: impl ToString) -> Result<T, ErrorMessage>;
+
+    /// Like [log_err_context](LogWithContext::log_err_context), but for errors, that are not fatal:
+    /// The chain is logged at the given [Level] and the error is replaced by [None], so the program can carry on.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// use log::Level;
+    /// fn read_cache() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+    ///
+    /// let cached = read_cache().log_err_context_or_none(Level::Info, "Failed to read cache");
+    /// // logs "Failed to read cache: Kind(NotFound)"
+    /// assert_eq!(cached, None);
+    /// ```
+    fn log_err_context_or_none(self, level: Level, context: impl ToString) -> Option<T>;
+
+    /// Like [log_err_context_or_none](LogWithContext::log_err_context_or_none) at [Level::Warn].
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// fn read_cache() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+    ///
+    /// let cached = read_cache().warn_err_context("Failed to read cache");
+    /// // logs "Failed to read cache: Kind(NotFound)"
+    /// assert_eq!(cached, None);
+    /// ```
+    fn warn_err_context(self, context: impl ToString) -> Option<T>;
+
+    /// Like [warn_err_context](LogWithContext::warn_err_context), but the context is only created in case of an error,
+    /// see [with_dyn_err_context](WithContext::with_dyn_err_context).
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// fn read_cache(path: &str) -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+    ///
+    /// let path = "cache.json";
+    /// let cached = read_cache(path).warn_dyn_err_context(|| format!("Failed to read cache '{path}'"));
+    /// // logs "Failed to read cache 'cache.json': Kind(NotFound)"
+    /// assert_eq!(cached, None);
+    /// ```
+    fn warn_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Option<T>;
+}
+
+impl<T, E, R: WithContext<T, E>> LogWithContext<T, E> for R {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn log_err_context(self, level: Level, context: impl ToString) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).inspect_err(|error| error.log(level))
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn log_err_context_or_none(self, level: Level, context: impl ToString) -> Option<T> {
+        match self.with_err_context(context) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                error.log(level);
+                None
+            }
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn warn_err_context(self, context: impl ToString) -> Option<T> {
+        self.log_err_context_or_none(Level::Warn, context)
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn warn_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Option<T> {
+        match self.with_dyn_err_context(context) {
+            Ok(value) => Some(value),
+            Err(error) => {
+                error.log(Level::Warn);
+                None
+            }
+        }
+    }
+}
+
+impl ErrorMessage {
+    /// Write the whole chain to the [log] crate at the given [Level], with the target `errors_with_context`.
+    /// The record contains the chain on a single line like [to_compact_string](ErrorMessage::to_compact_string).
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// use log::Level;
+    /// let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+    /// error.log(Level::Error);
+    /// // logs "Failed to sync: Connection refused"
+    /// ```
+    pub fn log(&self, level: Level) {
+        log::log!(target: TARGET, level, "{:#}", self);
+    }
+}
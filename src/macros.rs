@@ -0,0 +1,37 @@
+/// Add a formatted context to a [Result] or [Option] and record where it happened.
+///
+/// `err_context!(value, "format string", args...)` expands to
+/// [with_dyn_err_context](crate::WithContext::with_dyn_err_context) with a [format!]ted message,
+/// and stores the [file!], [line!], [column!] and [module_path!] of the call site in the new frame.
+/// The message is only formatted in the error case.
+///
+/// ```rust
+/// # use std::io;
+/// use errors_with_context::{err_context, ErrorMessage};
+/// fn read_config(path: &str) -> Result<String, io::Error> {
+///     Err(io::ErrorKind::NotFound.into())
+/// }
+///
+/// fn load_config() -> Result<String, ErrorMessage> {
+///     let path = "config.json";
+///     let config = err_context!(read_config(path), "Failed to read file '{path}'")?;
+///     Ok(config)
+/// }
+///
+/// let error = load_config().unwrap_err();
+/// # assert_eq!(error.to_string(), "Failed to read file 'config.json'\n  caused by: Kind(NotFound)");
+/// assert_eq!(error.location().unwrap().file(), file!());
+/// ```
+#[macro_export]
+macro_rules! err_context {
+    ($value:expr, $($arg:tt)+) => {
+        $crate::WithContext::with_dyn_err_context($value, || ::std::format!($($arg)+)).map_err(|error| {
+            error.with_location($crate::Location::new(
+                ::std::file!(),
+                ::std::line!(),
+                ::std::column!(),
+                ::std::option::Option::Some(::std::module_path!()),
+            ))
+        })
+    };
+}
@@ -0,0 +1,63 @@
+use crate::error_message::{ErrorMessage, Frame};
+
+impl ErrorMessage {
+    /// Render the chain as a nested Markdown list for pasting it into issues or chats,
+    /// where the indentation of the Display output gets lost.
+    ///
+    /// Backticks, asterisks and backslashes in messages are escaped.
+    /// The Debug output of a cause, that isn't an [ErrorMessage], is written as inline code, and
+    /// messages with multiple lines are written as a fenced code block below their bullet.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("No such file *config.toml*").context("Failed to start");
+    /// assert_eq!(error.render_markdown(), "\
+    /// - Failed to start
+    ///   - caused by: No such file \\*config.toml\\*
+    /// ");
+    /// ```
+    pub fn render_markdown(&self) -> String {
+        let mut markdown = String::new();
+        for (index, frame) in self.frames().enumerate() {
+            let indent = "  ".repeat(index);
+            let label = if index == 0 { "- " } else { "- caused by: " };
+            let text = frame.to_string();
+            markdown.push_str(&indent);
+            if text.contains('\n') {
+                let fence = "`".repeat(longest_backtick_run(&text).max(2) + 1);
+                markdown.push_str(label.trim_end());
+                markdown.push('\n');
+                for line in std::iter::once(fence.as_str()).chain(text.lines()).chain(std::iter::once(fence.as_str())) {
+                    markdown.push_str(&indent);
+                    markdown.push_str("  ");
+                    markdown.push_str(line);
+                    markdown.push('\n');
+                }
+                continue;
+            }
+            markdown.push_str(label);
+            match frame {
+                Frame::Cause(_) => {
+                    // Inline code can't contain escapes, so it needs more backticks than the text itself
+                    let ticks = "`".repeat(longest_backtick_run(&text) + 1);
+                    let padding = if text.starts_with('`') || text.ends_with('`') { " " } else { "" };
+                    markdown.push_str(&format!("{ticks}{padding}{text}{padding}{ticks}"));
+                }
+                Frame::Message(_) => {
+                    for c in text.chars() {
+                        if matches!(c, '`' | '*' | '\\') {
+                            markdown.push('\\');
+                        }
+                        markdown.push(c);
+                    }
+                }
+            }
+            markdown.push('\n');
+        }
+        markdown
+    }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
@@ -0,0 +1,21 @@
+use crate::error_message::ErrorMessage;
+use miette::Diagnostic;
+use std::fmt::Display;
+
+/// Exposes the [error code](ErrorMessage::error_code), the [id](ErrorMessage::id) or else the [exit code](ErrorMessage::exit_code) as the code
+/// and the [hints](ErrorMessage::hints) as the help text, one per line.
+/// The causes are reported through [source](std::error::Error::source), like for every other error.
+impl Diagnostic for ErrorMessage {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        match (self.error_code(), self.id()) {
+            (Some(code), _) => Some(Box::new(code)),
+            (None, Some(id)) => Some(Box::new(id)),
+            (None, None) => self.exit_code().map(|code| Box::new(code) as Box<dyn Display>),
+        }
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let hints: Vec<_> = self.hints().collect();
+        if hints.is_empty() { None } else { Some(Box::new(hints.join("\n"))) }
+    }
+}
@@ -1,8 +1,10 @@
+use std::borrow::Cow;
 use std::convert::Infallible;
 use crate::error_message::ErrorMessage;
 use crate::WithContext;
 
 impl<T> WithContext<T, Infallible> for Option<T> {
+    #[cfg_attr(feature = "location", track_caller)]
     fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
         match self {
             Some(value) => Ok(value),
@@ -11,12 +13,40 @@ impl<T> WithContext<T, Infallible> for Option<T> {
             }
         }
     }
-    fn with_dyn_err_context(self, context: impl FnOnce() -> String) -> Result<T, ErrorMessage> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_static(self, context: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(ErrorMessage::new_static(context)),
+        }
+    }
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
         match self {
             Some(value) => Ok(value),
             None => {
-                ErrorMessage::err(context())
+                Err(ErrorMessage::new_static(context()))
             }
         }
     }
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_id(id))
+    }
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_code(self, code: impl ToString, context: impl ToString) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_code(code))
+    }
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_kv<K: ToString, V: ToString>(self, context: impl ToString, details: impl IntoIterator<Item = (K, V)>) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_details(details))
+    }
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context_kv<S: Into<Cow<'static, str>>, I: IntoIterator<Item = (K, V)>, K: ToString, V: ToString>(
+        self,
+        context: impl FnOnce() -> S,
+        details: impl FnOnce() -> I,
+    ) -> Result<T, ErrorMessage> {
+        self.with_dyn_err_context(context).map_err(|error| error.with_details(details()))
+    }
 }
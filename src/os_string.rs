@@ -0,0 +1,53 @@
+use crate::error_message::ErrorMessage;
+use std::ffi::{OsStr, OsString};
+
+/// This trait adds context to failed conversions of [OsStrings](OsString) into UTF-8,
+/// like for paths and environment variables, that aren't valid UTF-8.
+///
+/// It is implemented for the result of [into_string](OsString::into_string) and for [OsStr] itself, which replaces [to_str](OsStr::to_str).
+/// The innermost frame of the chain shows the value with all invalid characters replaced:
+///
+/// ```rust
+/// # use std::ffi::OsString;
+/// use errors_with_context::prelude::*;
+/// # fn func() -> Result<(), ErrorMessage> {
+/// let home = std::env::var_os("HOME").unwrap_or_default();
+/// let home = home.into_string().with_utf8_err_context("Failed to read $HOME")?;
+/// #   Ok(())
+/// # }
+/// # func().unwrap();
+/// ```
+/// prints
+/// ```text
+/// Failed to read $HOME
+///   caused by: value is not valid UTF-8: "/home/fo�o"
+/// ```
+pub trait OsStringErrors<T> {
+    /// Return the UTF-8 string or an [ErrorMessage] with the provided context.
+    fn with_utf8_err_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+}
+
+impl OsStringErrors<String> for Result<String, OsString> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_utf8_err_context(self, context: impl ToString) -> Result<String, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(value) => Err(not_utf8(&value).context(context)),
+        }
+    }
+}
+
+impl<'a> OsStringErrors<&'a str> for &'a OsStr {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_utf8_err_context(self, context: impl ToString) -> Result<&'a str, ErrorMessage> {
+        match self.to_str() {
+            Some(value) => Ok(value),
+            None => Err(not_utf8(self).context(context)),
+        }
+    }
+}
+
+#[cfg_attr(feature = "location", track_caller)]
+fn not_utf8(value: &OsStr) -> ErrorMessage {
+    ErrorMessage::new(format!("value is not valid UTF-8: {:?}", value.to_string_lossy()))
+}
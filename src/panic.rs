@@ -0,0 +1,190 @@
+use crate::display::Aligned;
+use crate::ErrorMessage;
+use std::any::Any;
+use std::fmt;
+use std::fmt::Write;
+use std::io;
+use std::io::Write as _;
+use std::panic::{Location, PanicHookInfo, UnwindSafe};
+
+/// Replace the panic hook with one, that prints panics like the chain of an [ErrorMessage]:
+///
+/// ```text
+/// Thread 'main' panicked (at src/main.rs:42:9)
+///   caused by: called `Option::unwrap()` on a `None` value
+/// ```
+///
+/// If the panic payload is an [ErrorMessage], like after `std::panic::panic_any(error)` with the `send` feature,
+/// its whole chain is printed below.
+/// Use [install_panic_hook_chained] to keep the previous hook running as well
+/// and [restore_default_panic_hook] to go back to the default hook of the standard library.
+///
+/// ```rust
+/// errors_with_context::install_panic_hook();
+/// # errors_with_context::restore_default_panic_hook();
+/// ```
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(print_panic));
+}
+
+/// Like [install_panic_hook], but the previous hook is run after printing the panic, for example to still report it to a crash reporter.
+///
+/// ```rust
+/// errors_with_context::install_panic_hook_chained();
+/// # errors_with_context::restore_default_panic_hook();
+/// ```
+pub fn install_panic_hook_chained() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        print_panic(info);
+        previous(info);
+    }));
+}
+
+/// Go back to the default panic hook of the standard library, after [install_panic_hook] replaced it.
+pub fn restore_default_panic_hook() {
+    drop(std::panic::take_hook());
+}
+
+fn print_panic(info: &PanicHookInfo) {
+    let thread = std::thread::current();
+    let mut report = String::new();
+    let _ = write_panic(thread.name(), info.payload(), info.location(), &mut report);
+    report.push('\n');
+    // There is nowhere left to report a failure to write to stderr to
+    let _ = io::stderr().lock().write_all(report.as_bytes());
+}
+
+/// Writes a panic like the chain of an [ErrorMessage], the panic itself being the outermost frame
+pub(crate) fn write_panic<W: fmt::Write>(thread: Option<&str>, payload: &(dyn Any + Send), location: Option<&Location>, w: &mut W) -> fmt::Result {
+    match thread {
+        Some(name) => write!(w, "Thread '{name}' panicked")?,
+        None => w.write_str("Thread panicked")?,
+    }
+    if let Some(location) = location {
+        write!(w, " (at {}:{}:{})", location.file(), location.line(), location.column())?;
+    }
+    w.write_str("\n  caused by: ")?;
+    if let Some(error) = payload.downcast_ref::<ErrorMessage>() {
+        return error.write_chain(w);
+    }
+    let message = payload_message(payload).unwrap_or("Box<dyn Any>");
+    Aligned { w, indent: "  caused by: ".len() }.write_str(message)
+}
+
+/// The message of a panic, if its payload is a string, like for all panics created with [panic!]
+pub(crate) fn payload_message(payload: &(dyn Any + Send)) -> Option<&str> {
+    payload.downcast_ref::<&str>().copied().or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+}
+
+/// Run the closure and turn a panic inside of it into an [ErrorMessage] with the given context.
+///
+/// The message of the panic becomes the cause. Panics without a string message,
+/// like a [panic_any](std::panic::panic_any) with a number, are caused by `panic with non-string payload`.
+/// Like [catch_unwind](std::panic::catch_unwind), this only catches panics, that unwind, and the panic hook still runs.
+///
+/// ```rust
+/// use errors_with_context::catch_unwind_with_context;
+/// let result = catch_unwind_with_context("Failed to parse the config", || {
+///     panic!("Unexpected token");
+/// });
+/// assert_eq!(result.unwrap_err().to_string(), "Failed to parse the config\n  caused by: Unexpected token");
+/// ```
+#[cfg_attr(feature = "location", track_caller)]
+pub fn catch_unwind_with_context<T>(context: impl ToString, f: impl FnOnce() -> T + UnwindSafe) -> Result<T, ErrorMessage> {
+    match std::panic::catch_unwind(f) {
+        Ok(value) => Ok(value),
+        Err(payload) => {
+            let message = payload_message(payload.as_ref()).unwrap_or("panic with non-string payload");
+            Err(ErrorMessage::with_context(context, ErrorMessage::new(message)))
+        }
+    }
+}
+
+/// Like [catch_unwind_with_context], but for closures, that return a `Result<T, ErrorMessage>` themselves.
+/// Both a panic and an error returned by the closure get the context.
+///
+/// ```rust
+/// use errors_with_context::{try_catch_unwind_with_context, ErrorMessage};
+/// let result: Result<(), _> = try_catch_unwind_with_context("Failed to parse the config", || {
+///     ErrorMessage::err("Unexpected token")
+/// });
+/// assert_eq!(result.unwrap_err().to_string(), "Failed to parse the config\n  caused by: Unexpected token");
+/// ```
+#[cfg_attr(feature = "location", track_caller)]
+pub fn try_catch_unwind_with_context<T>(context: impl ToString, f: impl FnOnce() -> Result<T, ErrorMessage> + UnwindSafe) -> Result<T, ErrorMessage> {
+    match std::panic::catch_unwind(f) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(error)) => Err(ErrorMessage::with_context(context, error)),
+        Err(payload) => {
+            let message = payload_message(payload.as_ref()).unwrap_or("panic with non-string payload");
+            Err(ErrorMessage::with_context(context, ErrorMessage::new(message)))
+        }
+    }
+}
+
+/// This trait adds context to the result of [joining](std::thread::JoinHandle::join) a thread, that panicked.
+///
+/// The message of the panic becomes the cause. Panics without a string message are caused by `thread panicked with non-string payload`,
+/// and a panic with an [ErrorMessage] as its payload, like from [panic_any](std::panic::panic_any), keeps its whole chain.
+///
+/// ```rust
+/// use errors_with_context::prelude::*;
+/// let worker = std::thread::spawn(|| panic!("Disk is full"));
+/// let error = worker.join().join_with_err_context("Worker failed").unwrap_err();
+/// assert_eq!(error.to_string(), "Worker failed\n  caused by: Disk is full");
+/// ```
+pub trait JoinErrors<T> {
+    /// Return the value returned by the thread or an [ErrorMessage] with the provided context, if the thread panicked.
+    fn join_with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+}
+
+/// This trait adds context to the result of joining a thread, that returns a `Result<T, ErrorMessage>` itself.
+/// Both a panic and an error returned by the thread get the context, so they end up in one chain.
+///
+/// Sending an [ErrorMessage] back from a thread needs the `send` feature.
+///
+/// ```rust
+/// # #[cfg(feature = "send")]
+/// # {
+/// use errors_with_context::prelude::*;
+/// let worker = std::thread::spawn(|| ErrorMessage::err::<()>("Disk is full"));
+/// let error = worker.join().try_join_with_err_context("Worker failed").unwrap_err();
+/// assert_eq!(error.to_string(), "Worker failed\n  caused by: Disk is full");
+/// # }
+/// ```
+pub trait TryJoinErrors<T> {
+    /// Return the value returned by the thread or an [ErrorMessage] with the provided context,
+    /// if the thread panicked or returned an error.
+    fn try_join_with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+}
+
+impl<T> JoinErrors<T> for std::thread::Result<T> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn join_with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(payload) => Err(joined_panic(payload).context(context)),
+        }
+    }
+}
+
+impl<T> TryJoinErrors<T> for std::thread::Result<Result<T, ErrorMessage>> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn try_join_with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(error)) => Err(error.context(context)),
+            Err(payload) => Err(joined_panic(payload).context(context)),
+        }
+    }
+}
+
+/// The chain of a thread, that panicked
+#[cfg_attr(feature = "location", track_caller)]
+fn joined_panic(payload: Box<dyn Any + Send>) -> ErrorMessage {
+    match payload.downcast::<ErrorMessage>() {
+        Ok(error) => *error,
+        Err(payload) => ErrorMessage::new(payload_message(payload.as_ref()).unwrap_or("thread panicked with non-string payload")),
+    }
+}
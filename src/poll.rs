@@ -0,0 +1,58 @@
+use crate::error_message::ErrorMessage;
+use crate::WithContext;
+use std::borrow::Cow;
+use std::task::Poll;
+
+/// [WithContext] for `Poll<Result<T, E>>`, like the results of [Future](std::future::Future)s and `AsyncRead`s,
+/// that are implemented by hand.
+///
+/// [WithContext] returns a [Result], so this is a separate trait with the same methods, that return a [Poll] instead.
+/// [Pending](Poll::Pending) passes through untouched and the context is only built for an error.
+///
+/// ```rust
+/// # use std::{io, task::Poll};
+/// use errors_with_context::prelude::*;
+/// let poll: Poll<Result<usize, io::Error>> = Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+/// let poll = poll.with_err_context("Failed to read the frame header");
+/// # let Poll::Ready(result) = poll else { unreachable!() };
+/// # assert_eq!(result.unwrap_err().to_string(), "Failed to read the frame header\n  caused by: Kind(UnexpectedEof)");
+/// ```
+pub trait PollWithContext<T, E> {
+    /// Like [with_err_context](WithContext::with_err_context), but for a [Poll].
+    fn with_err_context(self, context: impl ToString) -> Poll<Result<T, ErrorMessage>>;
+
+    /// Like [with_dyn_err_context](WithContext::with_dyn_err_context), but for a [Poll].
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Poll<Result<T, ErrorMessage>>;
+
+    /// Like [with_err_context_id](WithContext::with_err_context_id), but for a [Poll].
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Poll<Result<T, ErrorMessage>>;
+}
+
+impl<T, E> PollWithContext<T, E> for Poll<Result<T, E>>
+where
+    Result<T, E>: WithContext<T, E>,
+{
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context(self, context: impl ToString) -> Poll<Result<T, ErrorMessage>> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.with_err_context(context)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Poll<Result<T, ErrorMessage>> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.with_dyn_err_context(context)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Poll<Result<T, ErrorMessage>> {
+        match self {
+            Poll::Ready(result) => Poll::Ready(result.with_err_context_id(context, id)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::error_message::ErrorMessage;
+use crate::WithContext;
+use std::ffi::OsStr;
+use std::process::{Command, ExitStatus, Output, Stdio};
+
+/// How many bytes of stderr are kept in the error, the end of it usually tells what went wrong
+const STDERR_LIMIT: usize = 2048;
+
+/// This trait turns processes, that didn't exit successfully, into [ErrorMessages](ErrorMessage).
+///
+/// The innermost frame of the chain says, which code the process exited with or which signal killed it.
+/// For an [Output], the captured stderr is attached as the detail `stderr`, limited to its last 2048 bytes.
+///
+/// ```rust
+/// # use std::process::Command;
+/// use errors_with_context::prelude::*;
+/// # #[cfg(unix)]
+/// # {
+/// let output = Command::new("sh").args(["-c", "echo 'No such device' >&2; exit 3"]).output().unwrap();
+/// let error = output.success_or_err_context("Failed to mount the backup drive").unwrap_err();
+/// assert_eq!(error.to_string(), "\
+/// Failed to mount the backup drive
+///   caused by: process exited with code 3
+///     stderr: No such device");
+/// # }
+/// ```
+pub trait ProcessErrors: Sized {
+    /// Return the value itself, if the process exited successfully, or an [ErrorMessage] with the provided context.
+    fn success_or_err_context(self, context: impl ToString) -> Result<Self, ErrorMessage>;
+}
+
+impl ProcessErrors for ExitStatus {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn success_or_err_context(self, context: impl ToString) -> Result<ExitStatus, ErrorMessage> {
+        if self.success() {
+            return Ok(self);
+        }
+        Err(failed(self).context(context))
+    }
+}
+
+impl ProcessErrors for Output {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn success_or_err_context(self, context: impl ToString) -> Result<Output, ErrorMessage> {
+        if self.status.success() {
+            return Ok(self);
+        }
+        let mut error = failed(self.status);
+        let stderr = String::from_utf8_lossy(&self.stderr);
+        let stderr = stderr.trim_end();
+        if !stderr.is_empty() {
+            error = error.with_detail("stderr", truncate_start(stderr, STDERR_LIMIT));
+        }
+        Err(error.context(context))
+    }
+}
+
+/// Run a command with the given arguments, wait for it to finish and return what it wrote to stdout.
+///
+/// Failing to start the command, the command exiting unsuccessfully and stdout not being valid UTF-8
+/// all become an [ErrorMessage] with the context `Failed to run command '<cmd>'`,
+/// where an unsuccessful exit is reported like by [success_or_err_context](ProcessErrors::success_or_err_context).
+///
+/// ```rust
+/// use errors_with_context::run_with_context;
+/// # #[cfg(unix)]
+/// # {
+/// let output = run_with_context("echo", ["Hello"]).unwrap();
+/// assert_eq!(output, "Hello\n");
+/// # }
+/// let error = run_with_context("swaynsg", ["-t", "get_outputs"]).unwrap_err();
+/// assert!(error.to_string().starts_with("Failed to run command 'swaynsg'\n  caused by: "));
+/// ```
+#[cfg_attr(feature = "location", track_caller)]
+pub fn run_with_context<I, S>(cmd: impl AsRef<OsStr>, args: I) -> Result<String, ErrorMessage>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let context = format!("Failed to run command '{}'", cmd.as_ref().to_string_lossy());
+    let output = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .with_err_context(&context)?
+        .success_or_err_context(&context)?;
+    String::from_utf8(output.stdout).with_err_context(&context)
+}
+
+#[cfg_attr(feature = "location", track_caller)]
+fn failed(status: ExitStatus) -> ErrorMessage {
+    if let Some(code) = status.code() {
+        return ErrorMessage::new(format!("process exited with code {code}"));
+    }
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return ErrorMessage::new(format!("process was killed by signal {signal}"));
+    }
+    ErrorMessage::new(format!("process failed with {status}"))
+}
+
+/// Keeps the end of the text, cut at a character boundary
+fn truncate_start(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_string();
+    }
+    let mut start = text.len() - limit;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[...] {}", &text[start..])
+}
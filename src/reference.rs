@@ -0,0 +1,40 @@
+use crate::error_message::ErrorMessage;
+use std::error::Error;
+
+/// [WithContextRef] is implemented for [Result].
+/// It builds an [ErrorMessage] from a borrowed [Result], so the original error can still be returned to the caller.
+///
+/// Since the error can't be moved out of the borrowed [Result], the [ErrorMessage] only contains its Debug text.
+/// **The original error type is not preserved** in the produced message.
+pub trait WithContextRef {
+    /// Returns [None] for `Ok(_)`.
+    /// For `Err(error)`, returns an [ErrorMessage] with the given context, caused by the Debug text of the error.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// fn save_cache() -> Result<(), io::Error> { Err(io::ErrorKind::PermissionDenied.into()) }
+    ///
+    /// fn update() -> Result<(), io::Error> {
+    ///     let result = save_cache();
+    ///     if let Some(error) = result.err_context_ref("Failed to save cache") {
+    ///         eprintln!("{error}");
+    /// #       assert_eq!(error.to_string(), "Failed to save cache\n  caused by: Kind(PermissionDenied)");
+    ///     }
+    ///     // The caller still gets the original io::Error
+    ///     result
+    /// }
+    /// # assert_eq!(update().unwrap_err().kind(), io::ErrorKind::PermissionDenied);
+    /// ```
+    fn err_context_ref(&self, context: impl ToString) -> Option<ErrorMessage>;
+}
+
+impl<T, E: Error> WithContextRef for Result<T, E> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn err_context_ref(&self, context: impl ToString) -> Option<ErrorMessage> {
+        match self {
+            Ok(_) => None,
+            Err(error) => Some(ErrorMessage::with_debug_context(context.to_string().into(), error)),
+        }
+    }
+}
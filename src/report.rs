@@ -0,0 +1,157 @@
+use crate::ErrorMessage;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::io;
+use std::process;
+use std::process::{ExitCode, Termination};
+
+/// The result of a program, that prints its [ErrorMessage] to stderr just like Display does.
+///
+/// Returning a `Result<(), ErrorMessage>` from `main` prints the error with its Debug output after an `Error: ` prefix.
+/// Returning a [Report] instead prints only the chain and exits with the [exit code](ErrorMessage::exit_code)
+/// of the error or 1, if it has none:
+/// ```rust
+/// use errors_with_context::{ErrorMessage, Report};
+/// use errors_with_context::prelude::*;
+/// fn run() -> Result<(), ErrorMessage> {
+///     // [...]
+/// #   Ok(())
+/// }
+///
+/// fn main() -> Report {
+///     run().with_err_context("Failed to start the program").into()
+/// }
+/// ```
+///
+/// It also works as the error of `main`, so `?` can be used directly.
+/// In that case, the standard library still adds the `Error: ` prefix:
+/// ```rust
+/// use errors_with_context::{ErrorMessage, Report};
+/// fn run() -> Result<(), ErrorMessage> {
+///     // [...]
+/// #   Ok(())
+/// }
+///
+/// fn main() -> Result<(), Report> {
+///     run()?;
+///     Ok(())
+/// }
+/// ```
+pub struct Report {
+    result: Result<(), ErrorMessage>,
+    verbosity: Option<u8>,
+}
+
+impl Report {
+    /// Print the chain with [display_verbosity](ErrorMessage::display_verbosity) and the given level,
+    /// for example depending on `-q`/`-v` command line flags, instead of the whole chain like Display does.
+    ///
+    /// ```rust
+    /// use errors_with_context::{ErrorMessage, Report};
+    /// use errors_with_context::prelude::*;
+    /// fn run() -> Result<(), ErrorMessage> {
+    ///     // [...]
+    /// #   Ok(())
+    /// }
+    ///
+    /// fn main() -> Report {
+    ///     let verbosity = 1 + std::env::args().filter(|arg| arg == "-v").count() as u8;
+    ///     Report::from(run().with_err_context("Failed to start the program")).with_verbosity(verbosity)
+    /// }
+    /// ```
+    pub fn with_verbosity(mut self, level: u8) -> Report {
+        self.verbosity = Some(level);
+        self
+    }
+
+    /// Writes the chain followed by a newline, if there is an error, and returns the [ExitCode] for it
+    pub(crate) fn write_report<W: io::Write>(&self, w: &mut W) -> ExitCode {
+        match &self.result {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => ExitCode::from(error.write_report_verbosity(w, self.verbosity)),
+        }
+    }
+}
+
+impl ErrorMessage {
+    /// Print the chain followed by a newline to stderr.
+    ///
+    /// Stderr is locked once for the whole chain, so its frames aren't interleaved with the output of other threads.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("I/O Error").context("Failed to read file");
+    /// error.eprint();
+    /// ```
+    pub fn eprint(&self) {
+        self.write_report(&mut io::stderr().lock());
+    }
+
+    /// Print the chain to stderr like [eprint](ErrorMessage::eprint) and terminate the process
+    /// with the [exit code](ErrorMessage::exit_code) of the error or 1, if it has none.
+    ///
+    /// Useful in programs, that can't return a [Report] from `main`.
+    /// Like [process::exit], this doesn't run any destructors.
+    ///
+    /// ```rust,no_run
+    /// use errors_with_context::ErrorMessage;
+    /// use errors_with_context::prelude::*;
+    /// fn run() -> Result<(), ErrorMessage> {
+    ///     // [...]
+    /// #   Ok(())
+    /// }
+    ///
+    /// if let Err(error) = run().with_err_context("Failed to start the program") {
+    ///     error.report_and_exit();
+    /// }
+    /// ```
+    pub fn report_and_exit(&self) -> ! {
+        let code = self.write_report(&mut io::stderr().lock());
+        process::exit(code.into())
+    }
+
+    /// Writes the chain followed by a newline and returns the exit code for it
+    pub(crate) fn write_report<W: io::Write>(&self, w: &mut W) -> u8 {
+        self.write_report_verbosity(w, None)
+    }
+
+    /// Like [write_report](ErrorMessage::write_report), but with [display_verbosity](ErrorMessage::display_verbosity), if a level is given
+    pub(crate) fn write_report_verbosity<W: io::Write>(&self, w: &mut W, verbosity: Option<u8>) -> u8 {
+        // There is nowhere left to report a failure to write to stderr to
+        let _ = match verbosity {
+            Some(level) => writeln!(w, "{}", self.display_verbosity(level)).and_then(|()| w.flush()),
+            None => self.write_to(w),
+        };
+        self.exit_code().unwrap_or(1)
+    }
+}
+
+impl Termination for Report {
+    fn report(self) -> ExitCode {
+        self.write_report(&mut io::stderr().lock())
+    }
+}
+
+impl From<ErrorMessage> for Report {
+    fn from(error: ErrorMessage) -> Self {
+        Report { result: Err(error), verbosity: None }
+    }
+}
+
+impl From<Result<(), ErrorMessage>> for Report {
+    fn from(result: Result<(), ErrorMessage>) -> Self {
+        Report { result, verbosity: None }
+    }
+}
+
+/// Writes the chain like Display, or with the [verbosity](Report::with_verbosity) of the [Report],
+/// so it stays readable after the `Error: ` prefix of a `Result` returned from `main`
+impl Debug for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (&self.result, self.verbosity) {
+            (Ok(()), _) => f.write_str("Ok"),
+            (Err(error), Some(level)) => Display::fmt(&error.display_verbosity(level), f),
+            (Err(error), None) => Display::fmt(error, f),
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use crate::error_message::ErrorMessage;
 use crate::WithContext;
+use std::borrow::Cow;
 use std::error::Error;
 
 #[cfg(feature = "send")]
 impl<T, E: Error + Send + 'static> WithContext<T, E> for Result<T, E> {
+    #[cfg_attr(feature = "location", track_caller)]
     fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
         match self {
             Ok(value) => Ok(value),
@@ -11,16 +13,50 @@ impl<T, E: Error + Send + 'static> WithContext<T, E> for Result<T, E> {
         }
     }
 
-    fn with_dyn_err_context(self, context: impl FnOnce() -> String) -> Result<T, ErrorMessage> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_static(self, context: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
         match self {
             Ok(value) => Ok(value),
-            Err(error) => Err(ErrorMessage::with_context(context(), error)),
+            Err(error) => Err(ErrorMessage::with_boxed_context(context.into(), Box::new(error), Some(std::any::type_name::<E>()))),
         }
     }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(ErrorMessage::with_boxed_context(context().into(), Box::new(error), Some(std::any::type_name::<E>()))),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_id(id))
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_code(self, code: impl ToString, context: impl ToString) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_code(code))
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_kv<K: ToString, V: ToString>(self, context: impl ToString, details: impl IntoIterator<Item = (K, V)>) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_details(details))
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context_kv<S: Into<Cow<'static, str>>, I: IntoIterator<Item = (K, V)>, K: ToString, V: ToString>(
+        self,
+        context: impl FnOnce() -> S,
+        details: impl FnOnce() -> I,
+    ) -> Result<T, ErrorMessage> {
+        self.with_dyn_err_context(context).map_err(|error| error.with_details(details()))
+    }
 }
 
 #[cfg(not(feature = "send"))]
 impl<T, E: Error + 'static> WithContext<T, E> for Result<T, E> {
+    #[cfg_attr(feature = "location", track_caller)]
     fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
         match self {
             Ok(value) => Ok(value),
@@ -28,10 +64,43 @@ impl<T, E: Error + 'static> WithContext<T, E> for Result<T, E> {
         }
     }
 
-    fn with_dyn_err_context(self, context: impl FnOnce() -> String) -> Result<T, ErrorMessage> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_static(self, context: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
         match self {
             Ok(value) => Ok(value),
-            Err(error) => Err(ErrorMessage::with_context(context(), error)),
+            Err(error) => Err(ErrorMessage::with_boxed_context(context.into(), Box::new(error), Some(std::any::type_name::<E>()))),
         }
     }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(ErrorMessage::with_boxed_context(context().into(), Box::new(error), Some(std::any::type_name::<E>()))),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_id(id))
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_code(self, code: impl ToString, context: impl ToString) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_code(code))
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_kv<K: ToString, V: ToString>(self, context: impl ToString, details: impl IntoIterator<Item = (K, V)>) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).map_err(|error| error.with_details(details))
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context_kv<S: Into<Cow<'static, str>>, I: IntoIterator<Item = (K, V)>, K: ToString, V: ToString>(
+        self,
+        context: impl FnOnce() -> S,
+        details: impl FnOnce() -> I,
+    ) -> Result<T, ErrorMessage> {
+        self.with_dyn_err_context(context).map_err(|error| error.with_details(details()))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,48 @@
+use crate::error_message::{ErrorMessage, Frame};
+use sentry::protocol::{Event, Exception, Level, Value};
+
+impl ErrorMessage {
+    /// Convert the chain into a Sentry [Event] with one exception per frame.
+    ///
+    /// The exceptions are ordered innermost first, like Sentry expects them, so the outermost context ends up at the top of the issue.
+    /// The type of the cause at the end of the chain is the name of its type, the type of all other frames is `ErrorMessage`.
+    /// The details of all frames are copied into the extra data of the event, where the outermost frame wins for duplicate keys.
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused").with_detail("host", "db").context("Failed to sync");
+    /// let event = error.to_sentry_event();
+    /// assert_eq!(event.exception.values.len(), 2);
+    /// assert_eq!(event.exception.values[1].value.as_deref(), Some("Failed to sync"));
+    /// assert_eq!(event.extra["host"], "db");
+    /// ```
+    pub fn to_sentry_event(&self) -> Event<'static> {
+        let mut event = Event { level: Level::Error, ..Event::default() };
+        let mut cause_type = None;
+        for frame in self.frames() {
+            let ty = match frame {
+                Frame::Message(_) => "ErrorMessage",
+                Frame::Cause(_) => cause_type.unwrap_or("Error"),
+            };
+            event.exception.values.push(Exception {
+                ty: ty.to_string(),
+                value: Some(frame.to_string()),
+                ..Exception::default()
+            });
+            if let Frame::Message(error) = frame {
+                cause_type = error.cause_type;
+                for (key, value) in error.details() {
+                    event.extra.entry(key.clone()).or_insert_with(|| Value::String(value.clone()));
+                }
+            }
+        }
+        event.exception.values.reverse();
+        event
+    }
+
+    /// Send the chain to Sentry as an event, see [to_sentry_event](ErrorMessage::to_sentry_event).
+    /// Does nothing, if no Sentry client is bound.
+    pub fn capture(&self) -> sentry::types::Uuid {
+        sentry::capture_event(self.to_sentry_event())
+    }
+}
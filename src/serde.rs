@@ -1,25 +1,134 @@
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
-use crate::error_message::ErrorMessage;
+use crate::display::more_causes;
+use crate::error_message::{Cause, ErrorMessage};
+use crate::json::MAX_DEPTH;
 
 impl Serialize for ErrorMessage {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_struct("ErrorMessage", 2)?;
-        s.serialize_field("message", &self.message)?;
-
-        if let Some(cause) = &self.cause {
-            if let Some(cause) = cause.downcast_ref::<ErrorMessage>() {
-                s.serialize_field("cause", cause)?;
-            } else {
-                s.serialize_field("cause", &Some(ErrorMessage::new(cause.to_string())))?;
-            }
+        Nested { error: self, depth: 1 }.serialize(serializer)
+    }
+}
+
+/// A frame and the number of frames, it is nested in, including itself.
+/// Below [MAX_DEPTH] frames, the rest of the chain is summarized, just like [to_json_string](ErrorMessage::to_json_string) does.
+struct Nested<'a> {
+    error: &'a ErrorMessage,
+    depth: usize,
+}
+
+impl Serialize for Nested<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let error = self.error;
+        #[cfg_attr(not(feature = "http"), allow(unused_mut))]
+        let mut len = 2
+            + usize::from(error.frame_id().is_some())
+            + usize::from(error.frame_code().is_some())
+            + usize::from(!error.details().is_empty())
+            + usize::from(!error.frame_hints().is_empty())
+            + usize::from(error.frame_transient())
+            + usize::from(cfg!(feature = "timestamp"))
+            + usize::from(!error.branches().is_empty());
+        #[cfg(feature = "http")]
+        {
+            len += usize::from(error.frame_status().is_some());
+        }
+        let mut s = serializer.serialize_struct("ErrorMessage", len)?;
+        s.serialize_field("message", &error.message)?;
+        if let Some(id) = error.frame_id() {
+            s.serialize_field("id", id)?;
+        } else {
+            s.skip_field("id")?;
+        }
+        if let Some(code) = error.frame_code() {
+            s.serialize_field("code", code)?;
         } else {
-            s.serialize_field("cause", &None::<ErrorMessage>)?;
+            s.skip_field("code")?;
         }
+        if !error.details().is_empty() {
+            s.serialize_field("details", &Details(error.details()))?;
+        } else {
+            s.skip_field("details")?;
+        }
+        if !error.frame_hints().is_empty() {
+            s.serialize_field("hints", error.frame_hints())?;
+        } else {
+            s.skip_field("hints")?;
+        }
+        if error.frame_transient() {
+            s.serialize_field("transient", &true)?;
+        } else {
+            s.skip_field("transient")?;
+        }
+        #[cfg(feature = "http")]
+        if let Some(status) = error.frame_status() {
+            s.serialize_field("status", &status.as_u16())?;
+        } else {
+            s.skip_field("status")?;
+        }
+        #[cfg(feature = "timestamp")]
+        s.serialize_field("timestamp", &error.timestamp_millis())?;
+        if !error.branches().is_empty() {
+            s.serialize_field("branches", &Branches { branches: error.branches(), depth: self.depth })?;
+        } else {
+            s.skip_field("branches")?;
+        }
+
+        match &error.cause {
+            Some(Cause::Message(cause)) if self.depth >= MAX_DEPTH => s.serialize_field("cause", &Leaf(more_causes(cause.depth())))?,
+            Some(Cause::Message(cause)) => s.serialize_field("cause", &Nested { error: cause, depth: self.depth + 1 })?,
+            Some(Cause::Other(cause)) => s.serialize_field("cause", &Leaf(cause.to_string()))?,
+            None => s.serialize_field("cause", &None::<ErrorMessage>)?,
+        }
+
+        s.end()
+    }
+}
+
+/// Serializes a foreign cause or the summary of the frames, that were left out, like a frame without a cause
+struct Leaf(String);
 
+impl Serialize for Leaf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("ErrorMessage", 2)?;
+        s.serialize_field("message", &self.0)?;
+        s.serialize_field("cause", &None::<ErrorMessage>)?;
         s.end()
     }
 }
+
+/// Serializes the branches of a frame, which are nested one frame deeper than the frame
+struct Branches<'a> {
+    branches: &'a [ErrorMessage],
+    depth: usize,
+}
+
+impl Serialize for Branches<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.branches.iter().map(|branch| Nested { error: branch, depth: self.depth + 1 }))
+    }
+}
+
+/// Serializes the details of a frame as a map, keeping their order
+struct Details<'a>(&'a [(String, String)]);
+
+impl Serialize for Details<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(self.0.iter().map(|(key, value)| (key, value)))
+    }
+}
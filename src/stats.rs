@@ -0,0 +1,150 @@
+use crate::ErrorMessage;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static REGISTRY: OnceLock<ErrorStats> = OnceLock::new();
+
+/// How [ErrorStats] groups errors into counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsKey {
+    /// Group by the message of the frame, that started the chain
+    Message,
+    /// Group by a hash over the message and the underlying cause of the frame, that started the chain.
+    /// Rendered as 16 hex digits.
+    Fingerprint,
+}
+
+/// A registry counting how often each kind of [ErrorMessage] is created.
+///
+/// Once [installed](ErrorStats::install), every newly created chain is counted exactly once.
+/// Adding context to an existing [ErrorMessage] doesn't count again.
+/// When no registry is installed, creating an error costs a single relaxed atomic load.
+///
+/// If more than `capacity` distinct keys are seen, the least recently counted key is dropped.
+///
+/// ```rust
+/// use errors_with_context::{ErrorMessage, ErrorStats};
+/// let stats = ErrorStats::install();
+///
+/// for _ in 0..3 {
+///     let _ = ErrorMessage::new("Failed to connect to database");
+/// }
+///
+/// assert!(stats.snapshot().contains(&("Failed to connect to database".to_string(), 3)));
+/// ```
+#[derive(Debug)]
+pub struct ErrorStats {
+    key: StatsKey,
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    tick: u64,
+    counts: HashMap<String, Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    count: u64,
+    last_seen: u64,
+}
+
+impl ErrorStats {
+    /// The default maximum number of distinct keys a registry keeps
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Create a registry, that is not installed.
+    /// Use [record](ErrorStats::record) to feed it manually or [install](ErrorStats::install_with) a global one.
+    ///
+    /// A `capacity` of 0 is treated as 1.
+    pub fn new(key: StatsKey, capacity: usize) -> ErrorStats {
+        ErrorStats { key, capacity: capacity.max(1), entries: Mutex::new(Entries::default()) }
+    }
+
+    /// Install the global registry grouping by [StatsKey::Message] with [DEFAULT_CAPACITY](ErrorStats::DEFAULT_CAPACITY).
+    ///
+    /// If a registry is already installed, it is returned unchanged.
+    pub fn install() -> &'static ErrorStats {
+        ErrorStats::install_with(StatsKey::Message, ErrorStats::DEFAULT_CAPACITY)
+    }
+
+    /// Install the global registry with the given configuration.
+    ///
+    /// If a registry is already installed, it is returned unchanged and the configuration is ignored.
+    pub fn install_with(key: StatsKey, capacity: usize) -> &'static ErrorStats {
+        let stats = REGISTRY.get_or_init(|| ErrorStats::new(key, capacity));
+        INSTALLED.store(true, Ordering::Relaxed);
+        stats
+    }
+
+    /// Returns the global registry, if it was installed
+    pub fn global() -> Option<&'static ErrorStats> {
+        if INSTALLED.load(Ordering::Relaxed) { REGISTRY.get() } else { None }
+    }
+
+    /// Count one occurrence of this error.
+    pub fn record(&self, error: &ErrorMessage) {
+        let key = match self.key {
+            StatsKey::Message => error.message.to_string(),
+            StatsKey::Fingerprint => format!("{:016x}", fingerprint(error)),
+        };
+
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.tick += 1;
+        let tick = entries.tick;
+        if let Some(entry) = entries.counts.get_mut(&key) {
+            entry.count += 1;
+            entry.last_seen = tick;
+            return;
+        }
+        if entries.counts.len() >= self.capacity {
+            let oldest = entries.counts.iter().min_by_key(|(_, entry)| entry.last_seen).map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.counts.remove(&oldest);
+            }
+        }
+        entries.counts.insert(key, Entry { count: 1, last_seen: tick });
+    }
+
+    /// Returns all keys with their counts, ordered by descending count.
+    pub fn snapshot(&self) -> Vec<(String, u64)> {
+        let entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut snapshot: Vec<(String, u64)> =
+            entries.counts.iter().map(|(key, entry)| (key.clone(), entry.count)).collect();
+        snapshot.sort_by(|(key_a, count_a), (key_b, count_b)| count_b.cmp(count_a).then_with(|| key_a.cmp(key_b)));
+        snapshot
+    }
+
+    /// Forget all counts.
+    pub fn reset(&self) {
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.counts.clear();
+    }
+}
+
+pub(crate) fn record(error: &ErrorMessage) {
+    if let Some(stats) = ErrorStats::global() {
+        stats.record(error);
+    }
+}
+
+/// FNV-1a over the message and the underlying cause, so it stays stable between program runs.
+fn fingerprint(error: &ErrorMessage) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    feed(error.message.as_bytes());
+    if let Some(cause) = &error.cause {
+        feed(b"\n");
+        feed(format!("{cause:?}").as_bytes());
+    }
+    hash
+}
@@ -0,0 +1,58 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+
+/// [WithContext](crate::WithContext) for `Result<T, String>` and `Result<T, &str>`.
+///
+/// [String] doesn't implement [Error](std::error::Error), so [WithContext](crate::WithContext) can't be implemented for it
+/// without conflicting with its implementation for all errors.
+/// This trait has the same methods, so it works the same way, once it is imported, for example with the [prelude](crate::prelude).
+/// The string becomes the innermost frame of the chain and is shown as it is.
+pub trait StringWithContext<T> {
+    /// Like [with_err_context](crate::WithContext::with_err_context), but for string errors.
+    ///
+    /// ```rust
+    /// use errors_with_context::prelude::*;
+    /// fn run_script() -> Result<(), String> { Err("boom".to_string()) }
+    /// let error = run_script().with_err_context("Failed to run the script").unwrap_err();
+    /// assert_eq!(error.to_string(), "Failed to run the script\n  caused by: boom");
+    /// ```
+    fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+
+    /// Like [with_dyn_err_context](crate::WithContext::with_dyn_err_context), but for string errors.
+    fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage>;
+
+    /// Like [with_err_context_id](crate::WithContext::with_err_context_id), but for string errors.
+    fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage>;
+}
+
+/// Implements [StringWithContext] for a string type, that becomes the message of the innermost frame
+macro_rules! string_with_context {
+    ($($string:ty),* $(,)?) => {
+        $(
+            impl<T> StringWithContext<T> for Result<T, $string> {
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
+                    match self {
+                        Ok(value) => Ok(value),
+                        Err(error) => Err(ErrorMessage::new(error).context(context)),
+                    }
+                }
+
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_dyn_err_context<S: Into<Cow<'static, str>>>(self, context: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
+                    match self {
+                        Ok(value) => Ok(value),
+                        Err(error) => Err(ErrorMessage::new(error).context_static(context())),
+                    }
+                }
+
+                #[cfg_attr(feature = "location", track_caller)]
+                fn with_err_context_id(self, context: impl ToString, id: impl Into<Cow<'static, str>>) -> Result<T, ErrorMessage> {
+                    self.with_err_context(context).map_err(|error| error.with_id(id))
+                }
+            }
+        )*
+    };
+}
+
+string_with_context!(String, &str);
@@ -0,0 +1,53 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+use std::fmt::Display;
+
+/// [StringifyWithContext] is implemented for every [Result], whose error implements [Display],
+/// including errors, that borrow data or aren't [Send], which can't become the cause of an [ErrorMessage].
+///
+/// The error is converted into the message of a new innermost frame, when the context is added.
+/// This is lossy by design: The chain still has two frames, but the original error, its type, its Debug output
+/// and its own [source](std::error::Error::source) are gone.
+/// Prefer [WithContext](crate::WithContext), whenever the error can be kept.
+pub trait StringifyWithContext<T> {
+    /// Like [with_err_context](crate::WithContext::with_err_context), but converts the error into a string frame.
+    ///
+    /// ```rust
+    /// # use std::fmt;
+    /// use errors_with_context::prelude::*;
+    /// #[derive(Debug)]
+    /// struct ParseError<'a> { input: &'a str }
+    /// impl fmt::Display for ParseError<'_> {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         write!(f, "'{}' is not a number", self.input)
+    ///     }
+    /// }
+    /// fn parse(input: &str) -> Result<u8, ParseError<'_>> { Err(ParseError { input }) }
+    ///
+    /// let input = String::from("four");
+    /// let error = parse(&input).with_err_context_stringify("Failed to parse the port").unwrap_err();
+    /// assert_eq!(error.to_string(), "Failed to parse the port\n  caused by: 'four' is not a number");
+    /// ```
+    fn with_err_context_stringify(self, reason: impl ToString) -> Result<T, ErrorMessage>;
+
+    /// Like [with_dyn_err_context](crate::WithContext::with_dyn_err_context), but converts the error into a string frame.
+    fn with_dyn_err_context_stringify<S: Into<Cow<'static, str>>>(self, reason: impl FnOnce() -> S) -> Result<T, ErrorMessage>;
+}
+
+impl<T, E: Display> StringifyWithContext<T> for Result<T, E> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_err_context_stringify(self, reason: impl ToString) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(ErrorMessage::new(error).context(reason)),
+        }
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn with_dyn_err_context_stringify<S: Into<Cow<'static, str>>>(self, reason: impl FnOnce() -> S) -> Result<T, ErrorMessage> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(error) => Err(ErrorMessage::new(error).context_static(reason())),
+        }
+    }
+}
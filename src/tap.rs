@@ -0,0 +1,63 @@
+use crate::error_message::ErrorMessage;
+use std::borrow::Cow;
+use std::fmt::Debug;
+
+/// [TapWithContext] is implemented for every [Result], whose error implements [Debug].
+///
+/// It is meant for code, that must keep its concrete error type, because the caller matches on it,
+/// but still wants to observe the chain at the point of failure:
+/// The chain, that [with_err_context](crate::WithContext::with_err_context) would have built, is created and reported
+/// to the [error hook](crate::set_error_hook), and the original [Result] is returned unchanged.
+///
+/// ```rust
+/// # use std::io;
+/// use errors_with_context::prelude::*;
+/// fn read_cache() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+///
+/// let result = read_cache().tap_err_context("Failed to read cache");
+/// // the hook sees "Failed to read cache\n  caused by: Kind(NotFound)"
+/// assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+/// ```
+pub trait TapWithContext<T, E> {
+    /// In case of an error, report the chain with the provided context and the Debug output of the error as its cause,
+    /// and return the [Result] unchanged.
+    fn tap_err_context(self, context: impl ToString) -> Result<T, E>;
+
+    /// Like [tap_err_context](TapWithContext::tap_err_context), but the context is built by the closure,
+    /// which gets access to the error.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// fn read_cache() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+    ///
+    /// let result = read_cache().tap_err_with(|error| format!("Failed to read cache ({})", error.kind()));
+    /// // the hook sees "Failed to read cache (entity not found)\n  caused by: Kind(NotFound)"
+    /// assert!(result.is_err());
+    /// ```
+    fn tap_err_with<S: Into<Cow<'static, str>>>(self, context: impl FnOnce(&E) -> S) -> Result<T, E>;
+}
+
+impl<T, E: Debug> TapWithContext<T, E> for Result<T, E> {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn tap_err_context(self, context: impl ToString) -> Result<T, E> {
+        if let Err(error) = &self {
+            tap(context.to_string().into(), error);
+        }
+        self
+    }
+
+    #[cfg_attr(feature = "location", track_caller)]
+    fn tap_err_with<S: Into<Cow<'static, str>>>(self, context: impl FnOnce(&E) -> S) -> Result<T, E> {
+        if let Err(error) = &self {
+            tap(context(error).into(), error);
+        }
+        self
+    }
+}
+
+/// Creating the chain reports it, so it is dropped right away
+#[cfg_attr(feature = "location", track_caller)]
+fn tap<E: Debug>(context: Cow<'static, str>, error: &E) {
+    ErrorMessage::with_debug_context(context, error);
+}
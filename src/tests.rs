@@ -72,7 +72,7 @@ fn test_functions() {
 }
 
 #[test]
-#[cfg(feature = "serde")]
+#[cfg(all(feature = "serde", not(feature = "timestamp")))]
 fn test_serialize_custom_base_error() {
     let message_text = "I/O Error";
     let result: Result<Infallible, _> = ErrorMessage::err(message_text)
@@ -100,7 +100,7 @@ fn test_serialize_custom_base_error() {
 }
 
 #[test]
-#[cfg(feature = "serde")]
+#[cfg(all(feature = "serde", not(feature = "timestamp")))]
 fn test_serialize() {
     let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
         .with_err_context("Failed to read file")
@@ -125,3 +125,2945 @@ fn test_serialize() {
   }
 }"#)
 }
+
+#[test]
+#[cfg(feature = "macros")]
+fn test_err_context_macro() {
+    let path = "config.toml";
+    let line = line!() + 1;
+    let result: Result<(), _> = crate::err_context!(Err(io::Error::from(ErrorKind::NotFound)), "Failed to read '{path}'");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let location = message.location().expect("Macro didn't record a location");
+
+    println!("test_err_context_macro():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to read 'config.toml'\n  caused by: Kind(NotFound)");
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), line);
+    assert_eq!(location.module_path(), Some(module_path!()));
+}
+
+#[test]
+#[cfg(feature = "stats")]
+fn test_stats_counts() {
+    use crate::{ErrorStats, StatsKey};
+    let stats = ErrorStats::new(StatsKey::Message, 10);
+    stats.record(&ErrorMessage::new("Failed to load configuration"));
+    stats.record(&ErrorMessage::new("Failed to connect"));
+    stats.record(&ErrorMessage::new("Failed to load configuration"));
+    assert_eq!(stats.snapshot(), vec![("Failed to load configuration".to_string(), 2), ("Failed to connect".to_string(), 1)]);
+
+    stats.reset();
+    assert_eq!(stats.snapshot(), vec![]);
+
+    let stats = ErrorStats::new(StatsKey::Fingerprint, 10);
+    stats.record(&ErrorMessage::with_context("Failed to read file", io::Error::from(ErrorKind::NotFound)));
+    stats.record(&ErrorMessage::with_context("Failed to read file", io::Error::from(ErrorKind::NotFound)));
+    stats.record(&ErrorMessage::with_context("Failed to read file", io::Error::from(ErrorKind::PermissionDenied)));
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.iter().map(|(_, count)| *count).collect::<Vec<_>>(), vec![2, 1]);
+    assert!(snapshot.iter().all(|(key, _)| key.len() == 16));
+}
+
+#[test]
+#[cfg(feature = "stats")]
+fn test_stats_capacity() {
+    use crate::{ErrorStats, StatsKey};
+    let stats = ErrorStats::new(StatsKey::Message, 2);
+    stats.record(&ErrorMessage::new("a"));
+    stats.record(&ErrorMessage::new("b"));
+    stats.record(&ErrorMessage::new("a"));
+    // "b" is the least recently counted key, so it has to make room for "c"
+    stats.record(&ErrorMessage::new("c"));
+    assert_eq!(stats.snapshot(), vec![("a".to_string(), 2), ("c".to_string(), 1)]);
+}
+
+#[test]
+#[cfg(feature = "stats")]
+fn test_stats_install() {
+    use crate::ErrorStats;
+    let stats = ErrorStats::install();
+    let count = |key: &str| stats.snapshot().into_iter().find(|(k, _)| k == key).map(|(_, count)| count);
+
+    let _ = ErrorMessage::new("test_stats_install: new");
+    let _: Result<Infallible, _> = ErrorMessage::err("test_stats_install: err")
+        .with_err_context("test_stats_install: wrapper")
+        .with_err_context("test_stats_install: wrapper");
+    let _: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound)).with_err_context("test_stats_install: io");
+    let _: Result<Infallible, _> = None.with_err_context("test_stats_install: none");
+
+    assert_eq!(count("test_stats_install: new"), Some(1));
+    assert_eq!(count("test_stats_install: err"), Some(1));
+    assert_eq!(count("test_stats_install: io"), Some(1));
+    assert_eq!(count("test_stats_install: none"), Some(1));
+    assert_eq!(count("test_stats_install: wrapper"), None);
+}
+
+#[test]
+fn test_err_context_ref() {
+    use crate::WithContextRef;
+    fn save_cache() -> io::Result<()> {
+        Err(io::Error::new(ErrorKind::NotFound, "Test message"))
+    }
+    fn load_cache() -> io::Result<u8> {
+        Ok(3)
+    }
+    let result = save_cache();
+    let message = result.err_context_ref("Failed to save cache")
+        .expect("Created an error and didn't get an error");
+
+    println!("test_err_context_ref():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to save cache
+  caused by: Custom { kind: NotFound, error: "Test message" }"#);
+    // The Debug text is kept as a foreign cause, just like with tap_err_context
+    assert_eq!(message.depth(), 2);
+    assert!(message.root_cause().downcast_ref::<ErrorMessage>().is_none());
+    assert_eq!(message.messages().last().as_deref(), Some(r#"Custom { kind: NotFound, error: "Test message" }"#));
+    let error = result.expect_err("The original result was changed");
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert_eq!(error.to_string(), "Test message");
+
+    let ok = load_cache();
+    assert!(ok.err_context_ref("Failed to save cache").is_none());
+    assert_eq!(ok.unwrap(), 3);
+}
+
+#[test]
+#[cfg(feature = "valuable")]
+fn test_valuable() {
+    use valuable::{NamedValues, Valuable, Value, Visit};
+
+    #[derive(Default)]
+    struct Recorder {
+        fields: Vec<String>,
+        message: Option<String>,
+        code: Option<String>,
+        details: Vec<(String, String)>,
+        causes: Vec<String>,
+    }
+
+    impl Visit for Recorder {
+        fn visit_value(&mut self, value: Value<'_>) {
+            match value {
+                Value::Structable(structable) => structable.visit(self),
+                Value::Listable(listable) => listable.visit(self),
+                Value::Tuplable(tuplable) => tuplable.visit(self),
+                Value::String(cause) => self.causes.push(cause.to_string()),
+                _ => panic!("Unexpected value {value:?}"),
+            }
+        }
+
+        fn visit_unnamed_fields(&mut self, values: &[Value<'_>]) {
+            match values {
+                [Value::String(key), Value::String(value)] => self.details.push((key.to_string(), value.to_string())),
+                _ => panic!("Unexpected detail {values:?}"),
+            }
+        }
+
+        fn visit_named_fields(&mut self, named_values: &NamedValues<'_>) {
+            for (field, value) in named_values {
+                self.fields.push(field.name().to_string());
+                match (field.name(), value) {
+                    ("message", Value::String(message)) => self.message = Some(message.to_string()),
+                    ("code", Value::String(code)) => self.code = Some(code.to_string()),
+                    ("code", Value::Unit) => self.code = None,
+                    ("details", value) | ("causes", value) => self.visit_value(*value),
+                    _ => panic!("Unexpected field {field:?}"),
+                }
+            }
+        }
+    }
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context_code("E1042", "Failed to read file")
+        .with_err_context_kv("Failed to load configuration", [("path", "/etc/app.toml"), ("attempt", "2")]);
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let mut recorder = Recorder::default();
+    valuable::visit(&message.as_value(), &mut recorder);
+    assert_eq!(recorder.fields, vec!["message", "code", "details", "causes"]);
+    assert_eq!(recorder.message.as_deref(), Some("Failed to load configuration"));
+    assert_eq!(recorder.code.as_deref(), Some("E1042"));
+    assert_eq!(recorder.details, [("path".to_string(), "/etc/app.toml".to_string()), ("attempt".to_string(), "2".to_string())]);
+    assert_eq!(recorder.causes, vec!["Failed to read file", "Kind(NotFound)"]);
+
+    let mut recorder = Recorder::default();
+    valuable::visit(&ErrorMessage::new("Connection refused").as_value(), &mut recorder);
+    assert_eq!(recorder.fields, vec!["message", "code", "details", "causes"]);
+    assert_eq!(recorder.code, None);
+    assert!(recorder.details.is_empty());
+}
+
+#[test]
+fn test_ids() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context_id("Failed to read file", "IO001")
+        .with_err_context_id("Failed to load configuration", "CFG001")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let message_string = message.to_string();
+
+    println!("test_ids():\n{}\n", message_string);
+    assert_eq!(message_string, r#"Failed to start the program
+  caused by: [CFG001] Failed to load configuration
+  caused by: [IO001] Failed to read file
+  caused by: Kind(NotFound)"#);
+    assert_eq!(message.id(), Some("CFG001"));
+    assert_eq!(message.ids().collect::<Vec<_>>(), vec!["CFG001", "IO001"]);
+    assert_eq!(ErrorMessage::new("No id").id(), None);
+}
+
+#[test]
+#[cfg(all(feature = "serde", not(feature = "timestamp")))]
+fn test_serialize_ids() {
+    let result: Result<Infallible, _> = ErrorMessage::err("I/O Error")
+        .with_err_context_id("Failed to load configuration", "CFG001");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let json = serde_json::to_string(&message)
+        .expect("Conversion to json failed");
+
+    println!("test_serialize_ids():\n{}\n", json);
+    assert_eq!(json, r#"{"message":"Failed to load configuration","id":"CFG001","cause":{"message":"I/O Error","cause":null}}"#)
+}
+
+#[test]
+fn test_display_verbosity() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context_id("Failed to load configuration", "CFG001")
+        .with_err_context("Failed to initialize")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_verbosity():\n{}\n", message.display_verbosity(1));
+    assert_eq!(message.display_verbosity(0).to_string(), "Failed to start the program");
+    assert_eq!(message.display_verbosity(1).to_string(), r#"Failed to start the program
+  caused by: Failed to initialize
+  caused by: [CFG001] Failed to load configuration
+  ... 2 more causes"#);
+    // With the location and backtrace features, level 2 also shows where every frame was created
+    #[cfg(not(any(feature = "location", feature = "backtrace", feature = "timestamp", feature = "thread_info")))]
+    assert_eq!(message.display_verbosity(2).to_string(), message.to_string());
+
+    // Level 1 shows the same as the precision flag, including details and hints
+    let message = message.with_detail("path", "/etc/app.toml").with_hint("run `myapp init` to create the config");
+    assert_eq!(message.display_verbosity(1).to_string(), format!("{message:.3}"));
+    assert!(message.display_verbosity(1).to_string().starts_with("Failed to start the program\n  path: /etc/app.toml\n"));
+    assert!(message.display_verbosity(1).to_string().ends_with("\n  hint: run `myapp init` to create the config"));
+}
+
+#[test]
+#[cfg(all(feature = "timestamp", feature = "thread_info"))]
+fn test_display_verbosity_metadata() {
+    let message = ErrorMessage::new("Connection refused").context("Failed to sync");
+
+    let verbose = message.display_verbosity(2).to_string();
+    println!("test_display_verbosity_metadata():\n{}\n", verbose);
+    let mut lines = verbose.lines();
+    assert!(lines.next().is_some_and(|line| line.starts_with("Failed to sync (") && line.ends_with("Z)")));
+    let thread = format!("Z) (on thread {:?})", std::thread::current().name().unwrap());
+    assert!(lines.next().is_some_and(|line| line.starts_with("  caused by: Connection refused (") && line.ends_with(&thread)));
+}
+
+#[test]
+#[cfg(all(feature = "macros", not(any(feature = "location", feature = "backtrace", feature = "timestamp", feature = "thread_info"))))]
+fn test_display_verbosity_locations() {
+    let line = line!() + 1;
+    let result: Result<Infallible, _> = crate::err_context!(ErrorMessage::err("Value is missing"), "Failed to load '{}'", "config");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    assert_eq!(message.display_verbosity(2).to_string(), format!(r#"Failed to load 'config' (at {}:{line}:41)
+  caused by: Value is missing"#, file!()));
+}
+
+#[test]
+fn test_to_tree() {
+    use crate::ErrorNode;
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context_id("Failed to load configuration", "CFG001")
+        .map_err(|error| error.with_code("E1042"))
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let tree = message.to_tree();
+
+    assert_eq!(tree.message, "Failed to start the program");
+    assert_eq!(tree.id, None);
+    assert_eq!(tree.code, None);
+    let child = tree.child.as_deref().expect("Missing second frame");
+    assert_eq!(child.message, "Failed to load configuration");
+    assert_eq!(child.id.as_deref(), Some("CFG001"));
+    assert_eq!(child.code.as_deref(), Some("E1042"));
+    let leaf = child.child.as_deref().expect("Missing leaf frame");
+    assert_eq!(leaf, &ErrorNode { message: "entity not found".to_string(), id: None, code: None, details: Vec::new(), hints: Vec::new(), transient: false, child: None, branches: Vec::new() });
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_to_tree_roundtrip() {
+    use crate::ErrorNode;
+    let result: Result<Infallible, _> = ErrorMessage::err("I/O Error")
+        .with_err_context_id("Failed to load configuration", "CFG001");
+    let tree = result.expect_err("Created an error and didn't get an error").to_tree();
+    let json = serde_json::to_string(&tree).expect("Conversion to json failed");
+
+    assert_eq!(json, r#"{"message":"Failed to load configuration","id":"CFG001","child":{"message":"I/O Error","child":null}}"#);
+    assert_eq!(serde_json::from_str::<ErrorNode>(&json).expect("Conversion from json failed"), tree);
+}
+
+#[test]
+#[cfg(feature = "last_error")]
+fn test_last_error() {
+    use crate::last_error::{self, StoreErr};
+    assert!(last_error::take().is_none());
+
+    last_error::set(ErrorMessage::new("First"));
+    last_error::set(ErrorMessage::new("Second"));
+    assert_eq!(last_error::with_last(|error| error.map(|error| error.to_string())), Some("Second".to_string()));
+    assert_eq!(last_error::take().map(|error| error.to_string()), Some("Second".to_string()));
+    assert!(last_error::take().is_none());
+    assert!(last_error::with_last(|error| error.is_none()));
+
+    let value: Option<Infallible> = ErrorMessage::err("Stored").store_err();
+    assert!(value.is_none());
+    assert_eq!(Ok::<_, ErrorMessage>(5).store_err(), Some(5));
+    assert_eq!(last_error::take().map(|error| error.to_string()), Some("Stored".to_string()));
+
+    last_error::set(ErrorMessage::new("Main thread"));
+    std::thread::spawn(|| {
+        assert!(last_error::take().is_none());
+        last_error::set(ErrorMessage::new("Other thread"));
+    })
+    .join()
+    .expect("Thread panicked");
+    assert_eq!(last_error::take().map(|error| error.to_string()), Some("Main thread".to_string()));
+}
+
+#[test]
+fn test_source() {
+    use std::error::Error;
+    let result: Result<Infallible, _> = Err(io::Error::new(ErrorKind::NotFound, "Test message"))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let second = message.source().expect("Missing second frame");
+    assert_eq!(second.downcast_ref::<ErrorMessage>().map(|error| error.message()), Some("Failed to load configuration"));
+    let third = second.source().expect("Missing third frame");
+    assert_eq!(third.downcast_ref::<ErrorMessage>().map(|error| error.message()), Some("Failed to read file"));
+    let leaf = third.source().expect("Missing leaf");
+    assert_eq!(leaf.downcast_ref::<io::Error>().map(io::Error::kind), Some(ErrorKind::NotFound));
+    assert!(leaf.source().is_none());
+
+    assert!(ErrorMessage::new("No cause").source().is_none());
+}
+
+#[test]
+fn test_message() {
+    let message = ErrorMessage::new("I/O Error");
+    assert_eq!(message.message(), "I/O Error");
+    assert_eq!(message.into_message(), "I/O Error");
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context_id("Failed to load configuration", "CFG001");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.message(), "Failed to load configuration");
+    assert_eq!(message.into_message(), "Failed to load configuration");
+}
+
+#[test]
+fn test_cause() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let cause = message.cause().expect("Missing cause");
+    let cause = cause.downcast_ref::<ErrorMessage>().expect("Cause isn't an ErrorMessage");
+    assert_eq!(cause.message(), "Failed to read file");
+    let leaf = cause.cause().expect("Missing leaf");
+    assert_eq!(leaf.downcast_ref::<io::Error>().map(io::Error::kind), Some(ErrorKind::NotFound));
+
+    assert!(ErrorMessage::new("No cause").cause().is_none());
+}
+
+#[test]
+fn test_root_cause() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to open file")
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let root = message.root_cause().downcast_ref::<io::Error>().expect("Root cause isn't an io::Error");
+    assert_eq!(root.kind(), ErrorKind::NotFound);
+
+    let result: Result<Infallible, _> = ErrorMessage::err("I/O Error")
+        .with_err_context("Failed to read file");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let root = message.root_cause().downcast_ref::<ErrorMessage>().expect("Root cause isn't an ErrorMessage");
+    assert_eq!(root.message(), "I/O Error");
+
+    let message = ErrorMessage::new("No cause");
+    let root = message.root_cause().downcast_ref::<ErrorMessage>().expect("Root cause isn't an ErrorMessage");
+    assert!(std::ptr::eq(root, &message));
+}
+
+#[test]
+fn test_chain() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let mut chain = message.chain();
+    assert_eq!(chain.len(), 3);
+    let first = chain.next().and_then(|error| error.downcast_ref::<ErrorMessage>());
+    assert_eq!(first.map(ErrorMessage::message), Some("Failed to load configuration"));
+    assert_eq!(chain.len(), 2);
+    let second = chain.next().and_then(|error| error.downcast_ref::<ErrorMessage>());
+    assert_eq!(second.map(ErrorMessage::message), Some("Failed to read file"));
+    let leaf = chain.next().and_then(|error| error.downcast_ref::<io::Error>());
+    assert_eq!(leaf.map(io::Error::kind), Some(ErrorKind::NotFound));
+    assert_eq!(chain.len(), 0);
+    assert!(chain.next().is_none());
+
+    assert_eq!(ErrorMessage::new("No cause").chain().count(), 1);
+}
+
+#[test]
+fn test_messages() {
+    fn read_file(_path: &str) -> io::Result<()> {
+        Err(io::Error::from(ErrorKind::UnexpectedEof))
+    }
+    let config_path = "config.json";
+    let result = read_file(config_path)
+        .with_dyn_err_context(|| format!("Failed to read file '{}'", config_path))
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    assert_eq!(message.messages().collect::<Vec<_>>(), vec![
+        "Failed to start the program",
+        "Failed to load configuration",
+        "Failed to read file 'config.json'",
+        "Kind(UnexpectedEof)",
+    ]);
+    assert_eq!(ErrorMessage::new("No cause").messages().collect::<Vec<_>>(), vec!["No cause"]);
+}
+
+#[test]
+fn test_depth() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.depth(), 4);
+
+    let result: Result<Infallible, _> = ErrorMessage::err("I/O Error")
+        .with_err_context("Failed to read file");
+    assert_eq!(result.expect_err("Created an error and didn't get an error").depth(), 2);
+}
+
+#[test]
+fn test_downcast_ref() {
+    let result: Result<Infallible, _> = Err(io::Error::new(ErrorKind::NotFound, "Test message"))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let error = message.downcast_ref::<io::Error>().expect("Didn't find the io::Error");
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert_eq!(message.downcast_ref::<ErrorMessage>().map(ErrorMessage::message), Some("Failed to start the program"));
+    assert!(message.downcast_ref::<std::fmt::Error>().is_none());
+}
+
+#[test]
+fn test_is() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to open file")
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert!(message.is::<io::Error>());
+    assert!(message.is::<ErrorMessage>());
+    assert!(!message.is::<std::fmt::Error>());
+
+    let message = ErrorMessage::new("No cause");
+    assert!(message.is::<ErrorMessage>());
+    assert!(!message.is::<io::Error>());
+}
+
+#[test]
+fn test_into_cause() {
+    let message = ErrorMessage::with_context("Failed to read file", io::Error::new(ErrorKind::NotFound, "Test message"));
+    let cause = message.into_cause().expect("Missing cause");
+    let error = cause.downcast::<io::Error>().expect("Cause isn't an io::Error");
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert_eq!(error.to_string(), "Test message");
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let (message, cause) = result.expect_err("Created an error and didn't get an error").into_parts();
+    assert_eq!(message, "Failed to load configuration");
+    let cause = cause.expect("Missing cause").downcast::<ErrorMessage>().expect("Cause isn't an ErrorMessage");
+    assert_eq!(cause.to_string(), "Failed to read file\n  caused by: Kind(NotFound)");
+
+    assert!(ErrorMessage::new("No cause").into_cause().is_none());
+}
+
+#[test]
+fn test_downcast() {
+    let result: Result<Infallible, _> = Err(io::Error::new(ErrorKind::NotFound, "Test message"))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let message_string = message.to_string();
+
+    let message = message.downcast::<std::fmt::Error>().expect_err("Found an error, that isn't in the chain");
+    assert_eq!(message.to_string(), message_string);
+    assert_eq!(message.depth(), 3);
+
+    let inner = message.downcast::<ErrorMessage>().expect("Didn't find the ErrorMessage");
+    assert_eq!(inner.to_string(), message_string);
+
+    let error = inner.downcast::<io::Error>().expect("Didn't find the io::Error");
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    assert_eq!(error.to_string(), "Test message");
+}
+
+#[test]
+fn test_context() {
+    let message = ErrorMessage::new("I/O Error")
+        .context("Failed to read file")
+        .dyn_context(|| format!("Failed to load {}", "configuration"))
+        .context("Failed to start the program");
+    let message_string = message.to_string();
+
+    println!("test_context():\n{}\n", message_string);
+    assert_eq!(message_string, r#"Failed to start the program
+  caused by: Failed to load configuration
+  caused by: Failed to read file
+  caused by: I/O Error"#)
+}
+
+#[test]
+fn test_set_cause() {
+    let expected: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let expected = expected.expect_err("Created an error and didn't get an error").to_string();
+
+    let mut message = ErrorMessage::new("Failed to read file").context("Failed to load configuration");
+    message.set_cause(io::Error::from(ErrorKind::NotFound));
+    assert_eq!(message.to_string(), expected);
+
+    // A foreign leaf gets replaced
+    message.set_cause(io::Error::from(ErrorKind::PermissionDenied));
+    assert_eq!(message.to_string(), expected.replace("NotFound", "PermissionDenied"));
+
+    let message = ErrorMessage::new("Failed to read file")
+        .context("Failed to load configuration")
+        .with_cause(ErrorMessage::new("I/O Error"));
+    assert_eq!(message.to_string(), r#"Failed to load configuration
+  caused by: Failed to read file
+  caused by: I/O Error"#);
+}
+
+#[test]
+fn test_merge() {
+    let save: Result<Infallible, _> = ErrorMessage::err("Failed to write file")
+        .with_err_context("Failed to save document");
+    let rollback: Result<Infallible, _> = ErrorMessage::err("Failed to delete temporary file")
+        .with_err_context("Failed to clean up")
+        .with_err_context("Failed to roll back");
+    let message = save.expect_err("Created an error and didn't get an error")
+        .merge(rollback.expect_err("Created an error and didn't get an error"));
+
+    println!("test_merge():\n{}\n", message);
+    assert_eq!(message.depth(), 5);
+    assert_eq!(message.to_string(), r#"Failed to save document
+  caused by: Failed to write file
+  caused by: Failed to roll back
+  caused by: Failed to clean up
+  caused by: Failed to delete temporary file"#);
+
+    let message = ErrorMessage::with_context("Failed to write file", io::Error::from(ErrorKind::StorageFull))
+        .merge(ErrorMessage::new("Failed to roll back"));
+    assert_eq!(message.to_string(), r#"Failed to write file
+  caused by: Kind(StorageFull)
+  caused by: Failed to roll back"#);
+}
+
+#[test]
+fn test_map_messages() {
+    fn redact(message: String) -> String {
+        match message.find("token=") {
+            Some(start) => {
+                let end = message[start..].find(' ').map_or(message.len(), |end| start + end);
+                format!("{}token=<redacted>{}", &message[..start], &message[end..])
+            }
+            None => message,
+        }
+    }
+    let result: Result<Infallible, _> = Err(io::Error::new(ErrorKind::PermissionDenied, "token=abc was rejected"))
+        .with_err_context("Failed to call https://example.com/?token=abc")
+        .with_err_context_id("Failed to sync with token=abc for user", "SYNC001")
+        .with_err_context("Failed to run background job");
+    let message = result.expect_err("Created an error and didn't get an error").map_messages(redact);
+
+    println!("test_map_messages():\n{}\n", message);
+    assert_eq!(message.depth(), 4);
+    assert_eq!(message.to_string(), r#"Failed to run background job
+  caused by: [SYNC001] Failed to sync with token=<redacted> for user
+  caused by: Failed to call https://example.com/?token=<redacted>
+  caused by: Custom { kind: PermissionDenied, error: "token=<redacted> was rejected" }"#);
+}
+
+#[test]
+fn test_truncate_chain() {
+    fn chain() -> ErrorMessage {
+        let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+            .with_err_context("Failed to read file")
+            .with_err_context("Failed to load configuration")
+            .with_err_context("Failed to start the program");
+        result.expect_err("Created an error and didn't get an error")
+    }
+    let expected = chain().to_string();
+
+    assert_eq!(chain().truncate_chain(4).to_string(), expected);
+    assert_eq!(chain().truncate_chain(10).to_string(), expected);
+    assert_eq!(chain().truncate_chain(3).to_string(), r#"Failed to start the program
+  caused by: Failed to load configuration
+  caused by: ... 2 more causes"#);
+    assert_eq!(chain().truncate_chain(1).to_string(), r#"Failed to start the program
+  caused by: ... 3 more causes"#);
+    assert_eq!(ErrorMessage::new("No cause").truncate_chain(1).to_string(), "No cause");
+}
+
+#[test]
+fn test_from_error() {
+    let error = io::Error::new(ErrorKind::NotFound, "Test message");
+    let message = ErrorMessage::from_error(error);
+
+    assert_eq!(message.message(), "Test message");
+    assert_eq!(message.to_string(), r#"Test message
+  caused by: Custom { kind: NotFound, error: "Test message" }"#);
+    let error = message.downcast::<io::Error>().expect("Didn't find the io::Error");
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+}
+
+#[test]
+fn test_from_display() {
+    let path = Path::new("./config");
+    let message = ErrorMessage::from_display(path.display());
+    assert_eq!(message.to_string(), "./config");
+    assert!(message.cause().is_none());
+
+    let result: Result<Infallible, _> = ErrorMessage::err_from_display(42);
+    assert_eq!(result.expect_err("Created an error and didn't get an error").to_string(), "42");
+}
+
+#[test]
+fn test_partial_eq() {
+    let result: Result<Infallible, _> = Err(io::Error::new(ErrorKind::NotFound, "Test message"))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    assert_eq!(message, ErrorMessage::new("Test message").context("Failed to read file").context("Failed to load configuration"));
+    assert_ne!(message, ErrorMessage::new("Failed to read file").context("Failed to load configuration"));
+    assert_ne!(message, ErrorMessage::new("Other message").context("Failed to read file").context("Failed to load configuration"));
+    assert_ne!(message, ErrorMessage::new("Test message").context("Failed to read file"));
+    assert_eq!(ErrorMessage::new("a"), ErrorMessage::new("a").with_id("ID001"));
+}
+
+#[test]
+fn test_to_lines() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to read file 'config.json'")
+        .with_err_context_id("Failed to load configuration", "CFG001")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    assert_eq!(message.to_lines(), vec![
+        "Failed to start the program",
+        "[CFG001] Failed to load configuration",
+        "Failed to read file 'config.json'",
+        "Kind(UnexpectedEof)",
+    ]);
+    assert_eq!(message.to_lines().join("\n  caused by: "), message.to_string());
+    assert_eq!(ErrorMessage::new("No cause").to_lines(), vec!["No cause"]);
+}
+
+#[test]
+fn test_to_compact_string() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to parse line 3:\n  key = \"value")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let compact = message.to_compact_string();
+    println!("test_to_compact_string():\n{}\n", compact);
+    assert_eq!(compact, r#"Failed to start the program: Failed to load configuration: Failed to parse line 3:   key = "value: Kind(UnexpectedEof)"#);
+    assert_eq!(message.to_compact_string_with(" | ").matches(" | ").count(), 3);
+    assert_eq!(ErrorMessage::new("No cause").to_compact_string(), "No cause");
+}
+
+#[test]
+fn test_write_chain() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let mut report = String::from("error: ");
+    message.write_chain(&mut report).expect("Writing into a String can't fail");
+    println!("test_write_chain():\n{}\n", report);
+    assert_eq!(report, r#"error: Failed to load configuration
+  caused by: Failed to read file
+  caused by: Kind(NotFound)"#);
+    assert_eq!(report.strip_prefix("error: "), Some(message.to_string().as_str()));
+}
+
+#[test]
+fn test_write_to() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let mut buffer = Vec::new();
+    message.write_to(&mut buffer).expect("Writing into a Vec can't fail");
+    println!("test_write_to():\n{}\n", String::from_utf8_lossy(&buffer));
+    assert_eq!(buffer, b"Failed to load configuration\n  caused by: Failed to read file\n  caused by: Kind(NotFound)\n");
+}
+
+/// A writer, that accepts a few bytes and fails afterwards
+struct FailingWriter {
+    remaining: usize,
+    written: Vec<u8>,
+}
+
+impl io::Write for FailingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            return Err(io::Error::new(ErrorKind::BrokenPipe, "Writer is closed"));
+        }
+        let count = buf.len().min(self.remaining);
+        self.remaining -= count;
+        self.written.extend_from_slice(&buf[..count]);
+        Ok(count)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_write_to_failing_writer() {
+    let message = ErrorMessage::new("I/O Error").context("Failed to read file");
+
+    let mut writer = FailingWriter { remaining: 10, written: Vec::new() };
+    let error = message.write_to(&mut writer).expect_err("Wrote into a failing writer and didn't get an error");
+    println!("test_write_to_failing_writer():\n{}\n", error);
+    assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    assert_eq!(writer.written, b"Failed to ");
+}
+
+#[derive(Debug, PartialEq)]
+struct Version {
+    major: u32,
+    minor: u32,
+}
+
+#[test]
+fn test_ensure() {
+    assert!(crate::ensure(true, "Not shown").is_ok());
+    let message = crate::ensure(false, "Condition failed").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Condition failed");
+    let message = crate::ensure_dyn(false, || format!("Condition {} failed", 3)).expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Condition 3 failed");
+}
+
+#[test]
+fn test_ensure_eq() {
+    assert!(crate::ensure_eq(1, 1, "Not shown").is_ok());
+
+    let message = crate::ensure_eq(2, 1, "Unsupported version").expect_err("Created an error and didn't get an error");
+    println!("test_ensure_eq():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Unsupported version
+  left: 2
+ right: 1"#);
+
+    let name = String::from("test.file");
+    let message = crate::ensure_eq(name.as_str(), "other.file", "Unexpected file").expect_err("Created an error and didn't get an error");
+    println!("test_ensure_eq():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Unexpected file
+  left: "test.file"
+ right: "other.file""#);
+
+    let version = Version { major: 1, minor: 2 };
+    let message = crate::ensure_dyn_eq(&version, &Version { major: 1, minor: 3 }, || format!("Expected minor version {}", 3))
+        .expect_err("Created an error and didn't get an error");
+    println!("test_ensure_eq():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Expected minor version 3
+  left: Version { major: 1, minor: 2 }
+ right: Version { major: 1, minor: 3 }"#);
+}
+
+#[test]
+fn test_ensure_ne() {
+    assert!(crate::ensure_ne("a", "b", "Not shown").is_ok());
+
+    let message = crate::ensure_ne("", "", "Name must not be empty").expect_err("Created an error and didn't get an error");
+    println!("test_ensure_ne():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Name must not be empty
+  left: ""
+ right: """#);
+
+    let message = crate::ensure_ne(0, 0, "Divisor must not be zero").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Divisor must not be zero\n  left: 0\n right: 0");
+
+    let version = Version { major: 1, minor: 2 };
+    let message = crate::ensure_dyn_ne(&version, &Version { major: 1, minor: 2 }, || String::from("Version didn't change"))
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Version didn't change\n  left: Version { major: 1, minor: 2 }\n right: Version { major: 1, minor: 2 }");
+}
+
+#[test]
+fn test_from_errors() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::InvalidData)).with_err_context("Expected a string");
+    let name = result.expect_err("Created an error and didn't get an error").context("Invalid value for 'name'");
+    let port = ErrorMessage::new("Port out of range");
+    let message = ErrorMessage::from_errors("Invalid configuration", [name, port]);
+
+    println!("test_from_errors():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Invalid configuration
+  ├─ caused by: Invalid value for 'name'
+  │    caused by: Expected a string
+  │    caused by: Kind(InvalidData)
+  └─ caused by: Port out of range"#);
+    assert_eq!(message.branches().len(), 2);
+    assert_eq!(message.depth(), 1);
+    assert!(message.cause().is_none());
+    assert_eq!(message.root_cause().to_string(), message.to_string());
+    assert_eq!(message.branches()[0].root_cause().to_string(), "invalid data");
+
+    assert_eq!(ErrorMessage::from_errors("Invalid configuration", []).to_string(), "Invalid configuration");
+    assert_eq!(
+        ErrorMessage::from_errors("Invalid configuration", [ErrorMessage::new("Port out of range")]).to_string(),
+        "Invalid configuration\n  caused by: Port out of range"
+    );
+}
+
+#[test]
+fn test_from_errors_nested() {
+    let user = ErrorMessage::from_errors("Invalid user", [
+        ErrorMessage::new("Missing name"),
+        ErrorMessage::new("Expected a number").context("Invalid age"),
+    ]);
+    let port = ErrorMessage::new("Port out of range");
+    let host = ErrorMessage::new("Unknown host 'example'");
+    let message = ErrorMessage::from_errors("Invalid configuration", [user.context("Failed to load users"), port, host])
+        .context("Failed to start the program");
+
+    println!("test_from_errors_nested():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to start the program
+  caused by: Invalid configuration
+    ├─ caused by: Failed to load users
+    │    caused by: Invalid user
+    │      ├─ caused by: Missing name
+    │      └─ caused by: Invalid age
+    │           caused by: Expected a number
+    ├─ caused by: Port out of range
+    └─ caused by: Unknown host 'example'"#);
+    assert_eq!(message.depth(), 2);
+
+    let redacted = message.map_messages(|message| message.replace("example", "****"));
+    assert!(redacted.to_string().ends_with("└─ caused by: Unknown host '****'"));
+}
+
+#[test]
+#[cfg(all(feature = "serde", not(feature = "timestamp")))]
+fn test_serialize_branches() {
+    let message = ErrorMessage::from_errors("Invalid configuration", [ErrorMessage::new("Missing name"), ErrorMessage::new("Missing port")]);
+    let json = serde_json::to_string(&message)
+        .expect("Conversion to json failed");
+
+    println!("test_serialize_branches():\n{}\n", json);
+    assert_eq!(json, r#"{"message":"Invalid configuration","branches":[{"message":"Missing name","cause":null},{"message":"Missing port","cause":null}],"cause":null}"#)
+}
+
+#[test]
+fn test_details() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .map_err(|error| error.with_detail("path", "/etc/app.toml").with_detail("attempt", 3))
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_details():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to load configuration
+  caused by: Failed to read file
+    path: /etc/app.toml
+    attempt: 3
+  caused by: Kind(NotFound)"#);
+    assert!(message.details().is_empty());
+    let frame = message.cause().and_then(|cause| cause.downcast_ref::<ErrorMessage>()).expect("The cause is an ErrorMessage");
+    assert_eq!(frame.details(), [("path".to_string(), "/etc/app.toml".to_string()), ("attempt".to_string(), "3".to_string())]);
+}
+
+#[test]
+#[cfg(all(feature = "serde", not(feature = "timestamp")))]
+fn test_serialize_details() {
+    let message = ErrorMessage::new("Failed to read file").with_detail("path", "/etc/app.toml").with_detail("attempt", 3);
+    let json = serde_json::to_string(&message)
+        .expect("Conversion to json failed");
+
+    println!("test_serialize_details():\n{}\n", json);
+    assert_eq!(json, r#"{"message":"Failed to read file","details":{"path":"/etc/app.toml","attempt":"3"},"cause":null}"#)
+}
+
+#[derive(Debug, PartialEq)]
+struct RequestId {
+    id: u64,
+}
+
+#[test]
+fn test_attachments() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::ConnectionReset))
+        .with_err_context("Failed to read response")
+        .map_err(|error| error.attach(RequestId { id: 42 }))
+        .with_err_context("Failed to query the database")
+        .with_err_context("Failed to handle request");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_attachments():\n{}\n", message);
+    assert_eq!(message.get_attachment::<RequestId>(), Some(&RequestId { id: 42 }));
+    assert_eq!(message.get_attachment::<String>(), None);
+    assert_eq!(message.to_string(), r#"Failed to handle request
+  caused by: Failed to query the database
+  caused by: Failed to read response
+  caused by: Kind(ConnectionReset)"#);
+
+    let message = message.attach(RequestId { id: 7 });
+    assert_eq!(message.get_attachment::<RequestId>(), Some(&RequestId { id: 7 }));
+}
+
+#[test]
+fn test_set_message() {
+    fn chain() -> ErrorMessage {
+        let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+            .with_err_context("Failed to stat inode 1234")
+            .with_err_context("Failed to resolve path");
+        result.expect_err("Created an error and didn't get an error")
+    }
+    let lower = chain().to_lines()[1..].to_vec();
+
+    let mut message = chain();
+    message.set_message("Failed to open the file");
+    println!("test_set_message():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to open the file
+  caused by: Failed to stat inode 1234
+  caused by: Kind(NotFound)"#);
+    assert_eq!(message.to_lines()[1..], lower);
+
+    let message = chain().map_message(|message| format!("{message} (translated)"));
+    assert_eq!(message.message(), "Failed to resolve path (translated)");
+    assert_eq!(message.to_lines()[1..], lower);
+}
+
+#[test]
+fn test_io_kind() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.io_kind(), Some(ErrorKind::NotFound));
+    assert_eq!(message.os_error_code(), None);
+
+    let result: Result<Infallible, _> = Err(io::Error::from_raw_os_error(13))
+        .with_err_context("Failed to open file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.io_kind(), Some(ErrorKind::PermissionDenied));
+    assert_eq!(message.os_error_code(), Some(13));
+
+    let message = ErrorMessage::new("Invalid input").context("Failed to parse configuration");
+    assert_eq!(message.io_kind(), None);
+    assert_eq!(message.os_error_code(), None);
+}
+
+#[test]
+fn test_transient() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::TimedOut))
+        .with_err_context("Connection timed out")
+        .map_err(ErrorMessage::transient)
+        .with_err_context("Failed to fetch data")
+        .with_err_context("Failed to run job");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert!(message.is_transient());
+    assert!(message.context("Failed to run all jobs").is_transient());
+
+    assert!(!ErrorMessage::new("Invalid input").context("Failed to run job").is_transient());
+    assert!(!ErrorMessage::new("Invalid input").transient().with_transient(false).is_transient());
+}
+
+#[test]
+#[cfg(all(feature = "serde", not(feature = "timestamp")))]
+fn test_serialize_transient() {
+    let message = ErrorMessage::new("Connection timed out").transient().context("Failed to fetch data");
+    let json = serde_json::to_string(&message)
+        .expect("Conversion to json failed");
+
+    println!("test_serialize_transient():\n{}\n", json);
+    assert_eq!(json, r#"{"message":"Failed to fetch data","cause":{"message":"Connection timed out","transient":true,"cause":null}}"#)
+}
+
+#[test]
+fn test_display_alternate() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_alternate():\n{:#}\n", message);
+    assert_eq!(format!("{message}"), r#"Failed to start the program
+  caused by: Failed to load configuration
+  caused by: Kind(UnexpectedEof)"#);
+    assert_eq!(format!("{message:#}"), "Failed to start the program: Failed to load configuration: Kind(UnexpectedEof)");
+}
+
+#[test]
+fn test_display_precision() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_precision():\n{:.1}\n", message);
+    assert_eq!(format!("{message:.1}"), r#"Failed to start the program
+  ... 3 more causes"#);
+    assert_eq!(format!("{message:.2}"), r#"Failed to start the program
+  caused by: Failed to load configuration
+  ... 2 more causes"#);
+    assert_eq!(format!("{message:.4}"), message.to_string());
+    assert_eq!(format!("{message:.10}"), message.to_string());
+    assert_eq!(format!("{message:.0}"), format!("{message:.1}"));
+}
+
+#[test]
+fn test_display_root_first() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to read file 'config.json'")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_root_first():\n{}\n", message.display_root_first());
+    assert_eq!(message.display_root_first().to_string(), r#"Kind(UnexpectedEof)
+  which caused: Failed to read file 'config.json'
+  which caused: Failed to load configuration
+  which caused: Failed to start the program"#);
+    assert_eq!(message.to_string(), r#"Failed to start the program
+  caused by: Failed to load configuration
+  caused by: Failed to read file 'config.json'
+  caused by: Kind(UnexpectedEof)"#);
+    assert_eq!(ErrorMessage::new("No cause").display_root_first().to_string(), "No cause");
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to parse line 3:\nunexpected end of file")
+        .map_err(|error| error.with_detail("path", "/etc/app.toml"))
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error").with_hint("run `myapp init` to create the config");
+    assert_eq!(message.display_root_first().to_string(), r#"Kind(UnexpectedEof)
+  which caused: Failed to parse line 3:
+                unexpected end of file
+    path: /etc/app.toml
+  which caused: Failed to load configuration
+  hint: run `myapp init` to create the config"#);
+}
+
+#[test]
+fn test_display_numbered() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to read file 'config.json'")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_numbered():\n{}\n", message.display_numbered());
+    assert_eq!(message.display_numbered().to_string(), r#"0: Failed to start the program
+1: caused by: Failed to load configuration
+2: caused by: Failed to read file 'config.json'
+3: caused by: Kind(UnexpectedEof)"#);
+
+    let mut message = ErrorMessage::new("Failed to parse value");
+    for _ in 0..10 {
+        message = message.context("Failed to parse object");
+    }
+    let numbered = message.display_numbered().to_string();
+    assert!(numbered.starts_with(" 0: Failed to parse object\n 1: caused by: Failed to parse object\n"));
+    assert!(numbered.ends_with("\n10: caused by: Failed to parse value"));
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to parse line 3:\nunexpected end of file")
+        .map_err(|error| error.with_detail("path", "/etc/app.toml"))
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error").with_hint("run `myapp init` to create the config");
+    assert_eq!(message.display_numbered().to_string(), r#"0: Failed to load configuration
+1: caused by: Failed to parse line 3:
+              unexpected end of file
+     path: /etc/app.toml
+2: caused by: Kind(UnexpectedEof)
+  hint: run `myapp init` to create the config"#);
+}
+
+#[test]
+fn test_display_tree() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to parse line 3:\nunexpected end of file")
+        .with_err_context("Failed to load configuration")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_tree():\n{}\n", message.display_tree());
+    assert_eq!(message.display_tree().to_string(), r#"Failed to start the program
+╰─▶ Failed to load configuration
+    ╰─▶ Failed to parse line 3:
+        unexpected end of file
+        ╰─▶ Kind(UnexpectedEof)"#);
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to parse line 3:\nunexpected end of file")
+        .map_err(|error| error.with_detail("path", "/etc/app.toml"))
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error").with_hint("run `myapp init` to create the config");
+    assert_eq!(message.display_tree().to_string(), r#"Failed to load configuration
+╰─▶ Failed to parse line 3:
+    unexpected end of file
+      path: /etc/app.toml
+    ╰─▶ Kind(UnexpectedEof)
+  hint: run `myapp init` to create the config"#);
+}
+
+#[test]
+#[cfg(feature = "color")]
+fn test_display_colored() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_colored():\n{}\n", message.display_colored());
+    #[cfg(not(feature = "location"))]
+    assert_eq!(
+        message.display_colored().to_string(),
+        "\x1b[1;31mFailed to load configuration\x1b[0m\n  \x1b[2mcaused by:\x1b[0m Failed to read file\n  \x1b[2mcaused by:\x1b[0m \x1b[33mKind(NotFound)\x1b[0m"
+    );
+    assert!(!message.to_string().contains('\x1b'));
+    assert!(!format!("{message:?}").contains('\x1b'));
+}
+
+#[test]
+fn test_display_with_label() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Datei konnte nicht gelesen werden")
+        .with_err_context("Programm konnte nicht gestartet werden");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_with_label():\n{}\n", message.display_with_label("verursacht durch"));
+    assert_eq!(message.display_with_label("verursacht durch").to_string(), r#"Programm konnte nicht gestartet werden
+  verursacht durch: Datei konnte nicht gelesen werden
+  verursacht durch: Kind(UnexpectedEof)"#);
+    assert_eq!(message.display_with_label("").to_string(), r#"Programm konnte nicht gestartet werden
+  Datei konnte nicht gelesen werden
+  Kind(UnexpectedEof)"#);
+    assert_eq!(message.display_with_label("caused by").to_string(), message.to_string());
+
+    let message = ErrorMessage::from_errors("Ungültige Konfiguration", [ErrorMessage::new("Name fehlt"), ErrorMessage::new("Port fehlt")]);
+    assert_eq!(message.display_with_label("wegen").to_string(), r#"Ungültige Konfiguration
+  ├─ wegen: Name fehlt
+  └─ wegen: Port fehlt"#);
+}
+
+#[test]
+fn test_deep_chain() {
+    let mut message = ErrorMessage::new("Failed to connect");
+    for attempt in 0..100_000 {
+        message = message.context(format!("Retry {attempt} failed"));
+    }
+
+    let display = message.to_string();
+    assert_eq!(display.lines().count(), 100_001);
+    assert!(display.starts_with("Retry 99999 failed\n  caused by: Retry 99998 failed\n"));
+    assert!(display.ends_with("\n  caused by: Failed to connect"));
+    assert!(format!("{message:?}").contains("Failed to connect"));
+    assert_eq!(message.depth(), 100_001);
+    // Serializing summarizes everything below the first 100 frames
+    let json = message.to_json_string();
+    assert_eq!(json.matches("\"message\":").count(), 101);
+    assert!(json.ends_with(&(r#"{"message":"... 99901 more causes","cause":null}"#.to_string() + &"}".repeat(100))));
+    #[cfg(feature = "serde")]
+    assert_eq!(serde_json::to_string(&message).expect("Conversion to json failed"), json);
+    #[cfg(feature = "serde")]
+    assert!(serde_json::from_str::<serde_json::Value>(&json).is_ok(), "The nesting limit of serde_json was exceeded");
+    let tree = message.to_tree();
+    assert!(tree.clone() == tree);
+    drop(tree);
+    drop(message);
+}
+
+#[test]
+fn test_display_leaf_display() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::UnexpectedEof))
+        .with_err_context("Failed to read file 'config.json'")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_leaf_display():\n{}\n", message.display_leaf_display());
+    assert_eq!(message.to_string(), r#"Failed to load configuration
+  caused by: Failed to read file 'config.json'
+  caused by: Kind(UnexpectedEof)"#);
+    assert_eq!(message.display_leaf_display().to_string(), r#"Failed to load configuration
+  caused by: Failed to read file 'config.json'
+  caused by: unexpected end of file"#);
+}
+
+/// An error, whose Debug output is the same as its Display output
+struct PlainError;
+
+impl std::fmt::Display for PlainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Disk is full")
+    }
+}
+
+impl std::fmt::Debug for PlainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for PlainError {}
+
+#[test]
+fn test_display_leaf_both() {
+    let result: Result<Infallible, _> = Err(io::Error::from_raw_os_error(2))
+        .with_err_context("Failed to read file 'config.json'")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_leaf_both():\n{}\n", message.display_leaf_both());
+    let expected = format!(r#"Failed to load configuration
+  caused by: Failed to read file 'config.json'
+  caused by: {} [{:?}]"#, io::Error::from_raw_os_error(2), io::Error::from_raw_os_error(2));
+    assert_eq!(message.display_leaf_both().to_string(), expected);
+
+    let result: Result<Infallible, _> = Err(PlainError).with_err_context("Failed to write file");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.display_leaf_both().to_string(), "Failed to write file\n  caused by: Disk is full");
+}
+
+#[test]
+fn test_display_type_names() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_display_type_names():\n{}\n", message.display_type_names());
+    assert_eq!(message.display_type_names().to_string(), format!(r#"Failed to load configuration
+  caused by: Failed to read file
+  caused by [{}]: Kind(NotFound)"#, std::any::type_name::<io::Error>()));
+
+    let message = ErrorMessage::new("Failed to write file").with_cause(PlainError);
+    assert_eq!(
+        message.display_type_names().to_string(),
+        format!("Failed to write file\n  caused by [{}]: Disk is full", std::any::type_name::<PlainError>())
+    );
+    assert!(message.display_type_names().to_string().contains("tests::PlainError"));
+    assert_eq!(message.to_string(), "Failed to write file\n  caused by: Disk is full");
+}
+
+#[test]
+fn test_display_wrapped() {
+    let long = "Failed to parse the response of the update server, because it contained an unexpected field named 'download_url_for_the_latest_release_artifacts_v2' at position 1234 in the document";
+    let long = format!("{long} which was received");
+    assert_eq!(long.len(), 200);
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::InvalidData))
+        .with_err_context(long)
+        .with_err_context("Failed to check for updates");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    let wrapped = message.display_wrapped(40).to_string();
+    println!("test_display_wrapped():\n{}\n", wrapped);
+    assert_eq!(wrapped, r#"Failed to check for updates
+  caused by: Failed to parse the
+             response of the update
+             server, because it
+             contained an unexpected
+             field named
+             'download_url_for_the_lates
+             t_release_artifacts_v2' at
+             position 1234 in the
+             document which was received
+  caused by: Kind(InvalidData)"#);
+    assert!(wrapped.lines().all(|line| line.chars().count() <= 40));
+    assert_eq!(message.display_wrapped(usize::MAX - 1).to_string(), message.to_string());
+}
+
+#[test]
+fn test_dedup_chain() {
+    let mut result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::ConnectionRefused))
+        .with_err_context("Failed to connect");
+    for _ in 0..5 {
+        result = result.with_err_context("retrying request");
+    }
+    let message = result
+        .with_err_context("Failed to sync")
+        .with_err_context("Failed to connect")
+        .expect_err("Created an error and didn't get an error")
+        .dedup_chain();
+
+    println!("test_dedup_chain():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to connect
+  caused by: Failed to sync
+  caused by: retrying request (x5)
+  caused by: Failed to connect
+  caused by: Kind(ConnectionRefused)"#);
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let display = message.to_string();
+    assert_eq!(message.dedup_chain().to_string(), display);
+}
+
+#[test]
+#[cfg(all(feature = "pretty_debug_errors", not(any(feature = "location", feature = "timestamp"))))]
+fn test_debug_alternate() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_debug_alternate():\n{:#?}\n", message);
+    assert_eq!(format!("{message:?}"), message.to_string());
+    assert_eq!(format!("{message:#?}"), r#"ErrorMessage {
+    message: "Failed to load configuration",
+    cause: ErrorMessage {
+        message: "Failed to read file",
+        cause: Some(
+            Kind(
+                NotFound,
+            ),
+        ),
+    },
+}"#);
+}
+
+#[test]
+#[cfg(all(feature = "location", feature = "timestamp"))]
+fn test_debug_metadata() {
+    let line = line!() + 1;
+    let message = ErrorMessage::new("Connection refused");
+
+    let debug = format!("{message:#?}");
+    println!("test_debug_metadata():\n{}\n", debug);
+    assert!(debug.contains(&format!("    location: Location {{\n        file: {:?},\n        line: {line},\n", file!())));
+    assert!(debug.contains("    timestamp: SystemTime {"));
+}
+
+#[test]
+fn test_report() {
+    use crate::Report;
+    use std::process::ExitCode;
+
+    let result: Result<(), _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to start the program");
+    let report = Report::from(result);
+
+    let mut stderr = Vec::new();
+    assert_eq!(report.write_report(&mut stderr), ExitCode::FAILURE);
+    println!("test_report():\n{}\n", String::from_utf8_lossy(&stderr));
+    assert_eq!(stderr, b"Failed to start the program\n  caused by: Failed to read file\n  caused by: Kind(NotFound)\n");
+    assert_eq!(format!("{report:?}"), "Failed to start the program\n  caused by: Failed to read file\n  caused by: Kind(NotFound)");
+
+    let mut stderr = Vec::new();
+    assert_eq!(Report::from(Ok(())).write_report(&mut stderr), ExitCode::SUCCESS);
+    assert!(stderr.is_empty());
+
+    let message = ErrorMessage::new("Connection refused").context("Failed to sync").with_exit_code(75);
+    let report = Report::from(message).with_verbosity(0);
+    let mut stderr = Vec::new();
+    assert_eq!(report.write_report(&mut stderr), ExitCode::from(75));
+    assert_eq!(stderr, b"Failed to sync\n");
+    assert_eq!(format!("{report:?}"), "Failed to sync");
+    let mut stderr = Vec::new();
+    assert_eq!(Report::from(Ok(())).with_verbosity(2).write_report(&mut stderr), ExitCode::SUCCESS);
+    assert!(stderr.is_empty());
+}
+
+#[test]
+fn test_exit_code() {
+    use crate::Report;
+    use std::process::ExitCode;
+
+    let result: Result<(), _> = Err(io::Error::from(ErrorKind::ConnectionRefused))
+        .with_err_context("Failed to connect")
+        .map_err(|error| error.with_exit_code(75))
+        .with_err_context("Failed to send request")
+        .with_err_context("Failed to sync")
+        .with_err_context("Failed to run the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.exit_code(), Some(75));
+    assert_eq!(Report::from(message).write_report(&mut Vec::new()), ExitCode::from(75));
+
+    let message = ErrorMessage::new("Unknown flag").with_exit_code(75).context("Invalid usage").with_exit_code(2);
+    assert_eq!(message.exit_code(), Some(2));
+    assert_eq!(ErrorMessage::new("No code").context("Still no code").exit_code(), None);
+}
+
+#[test]
+fn test_eprint() {
+    let message = ErrorMessage::new("Unknown flag '--colour'").context("Failed to parse arguments");
+    let mut stderr = Vec::new();
+    assert_eq!(message.write_report(&mut stderr), 1);
+    let stderr = String::from_utf8(stderr).unwrap();
+    println!("test_eprint():\n{}\n", stderr);
+    assert_eq!(
+        stderr,
+        r#"Failed to parse arguments
+  caused by: Unknown flag '--colour'
+"#
+    );
+
+    let mut stderr = Vec::new();
+    assert_eq!(message.with_exit_code(2).write_report(&mut stderr), 2);
+    assert!(!stderr.is_empty());
+    assert_eq!(ErrorMessage::new("Failed").write_report(&mut FailingWriter { remaining: 0, written: Vec::new() }), 1);
+}
+
+#[test]
+#[cfg(not(feature = "timestamp"))]
+fn test_to_json_string() {
+    let message = ErrorMessage::new("Unexpected \"quote\" in C:\\path\n\tat line 1\u{0}\u{1f}\u{7f}")
+        .context("Failed to load »Einstellungen« 🦀")
+        .with_id("CFG001");
+    let json = message.to_json_string();
+
+    println!("test_to_json_string():\n{}\n", json);
+    assert_eq!(
+        json,
+        r#"{"message":"Failed to load »Einstellungen« 🦀","id":"CFG001","cause":{"message":"Unexpected \"quote\" in C:\\path\n\tat line 1\u0000\u001f"#.to_string()
+            + "\u{7f}\",\"cause\":null}}"
+    );
+    assert_eq!(ErrorMessage::new("\u{8}\u{c}\r").to_json_string(), r#"{"message":"\b\f\r","cause":null}"#);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_to_json_string_matches_serde() {
+    let result: Result<Infallible, _> = Err(io::Error::other("Permission \"denied\"\n"))
+        .with_err_context("Failed to read \\etc\\app.toml")
+        .map_err(|error| error.with_detail("path", "/etc/app.toml\u{1}").with_detail("attempt", 3).transient())
+        .with_err_context_id("Failed to load configuration", "CFG001");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let message = ErrorMessage::from_errors("Failed to start ✨", [message, ErrorMessage::new("Missing port")]);
+
+    let json = serde_json::to_string(&message).expect("Conversion to json failed");
+    println!("test_to_json_string_matches_serde():\n{}\n", json);
+    assert_eq!(message.to_json_string(), json);
+
+    let message = ErrorMessage::new("Single frame");
+    assert_eq!(message.to_json_string(), serde_json::to_string(&message).expect("Conversion to json failed"));
+}
+
+#[test]
+fn test_render_markdown() {
+    let result: Result<Infallible, _> = Err(io::Error::other("Found `*` in path"))
+        .with_err_context("Failed to expand `*.toml`")
+        .with_err_context("Failed to load **all** configuration files");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let markdown = message.render_markdown();
+
+    println!("test_render_markdown():\n{}\n", markdown);
+    assert_eq!(markdown, r#"- Failed to load \*\*all\*\* configuration files
+  - caused by: Failed to expand \`\*.toml\`
+    - caused by: ``Custom { kind: Other, error: "Found `*` in path" }``
+"#);
+
+    let message = ErrorMessage::new("expected `=`\n  name value\n       ^").context("Failed to parse config");
+    assert_eq!(message.render_markdown(), r#"- Failed to parse config
+  - caused by:
+    ```
+    expected `=`
+      name value
+           ^
+    ```
+"#);
+}
+
+#[test]
+fn test_multi_line_messages() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::InvalidData))
+        .with_err_context("Failed to parse line 3:\n  port = \"eighty\"")
+        .with_err_context("Failed to load configuration\nfrom /etc/app.toml")
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_multi_line_messages():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to start the program
+  caused by: Failed to load configuration
+             from /etc/app.toml
+  caused by: Failed to parse line 3:
+               port = "eighty"
+  caused by: Kind(InvalidData)"#);
+    #[cfg(not(any(feature = "location", feature = "backtrace", feature = "timestamp", feature = "thread_info")))]
+    assert_eq!(message.display_verbosity(2).to_string(), message.to_string());
+
+    let message = ErrorMessage::new("Invalid schema").context("Failed to validate\nthe configuration");
+    assert_eq!(message.to_string(), "Failed to validate\nthe configuration\n  caused by: Invalid schema");
+
+    let message = ErrorMessage::with_context("Failed to validate", SchemaError);
+    println!("test_multi_line_messages():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to validate
+  caused by: SchemaError {
+                 field: "port",
+             }"#);
+}
+
+struct SchemaError;
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Invalid field")
+    }
+}
+
+impl std::fmt::Debug for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Like `{:#?}` of a struct, which some error types use for their Debug output
+        f.write_str("SchemaError {\n    field: \"port\",\n}")
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[test]
+fn test_display_sanitized() {
+    let result: Result<Infallible, _> = Err(io::Error::other("\u{9b}2J"))
+        .with_err_context("Unexpected body '\x1b[31mERROR\x1b[0m\r2024-01-01 INFO: Login succeeded'")
+        .with_err_context("Failed to log in\nas 'admin'");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let sanitized = message.display_sanitized().to_string();
+
+    println!("test_display_sanitized():\n{}\n", sanitized);
+    assert_eq!(sanitized, r#"Failed to log in
+as 'admin'
+  caused by: Unexpected body '\x1b[31mERROR\x1b[0m\x0d2024-01-01 INFO: Login succeeded'
+  caused by: Custom { kind: Other, error: "\u{9b}2J" }"#);
+    assert!(message.to_string().contains("\x1b[31m"));
+}
+
+#[test]
+fn test_hints() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read '/etc/app.toml'")
+        .map_err(|error| error.with_hint("run `myapp init` to create the config").with_hint("or pass `--config <path>`"))
+        .with_err_context("Failed to load configuration")
+        .map_err(|error| error.with_hint("see `myapp help config`"))
+        .with_err_context("Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_hints():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to start the program
+  caused by: Failed to load configuration
+  caused by: Failed to read '/etc/app.toml'
+  caused by: Kind(NotFound)
+  hint: see `myapp help config`
+  hint: run `myapp init` to create the config
+  hint: or pass `--config <path>`"#);
+    assert_eq!(
+        message.hints().collect::<Vec<_>>(),
+        ["see `myapp help config`", "run `myapp init` to create the config", "or pass `--config <path>`"]
+    );
+    assert!(message.display_verbosity(1).to_string().ends_with("  ... 1 more cause\n  hint: see `myapp help config`\n  hint: run `myapp init` to create the config\n  hint: or pass `--config <path>`"));
+}
+
+#[test]
+#[cfg(all(feature = "serde", not(feature = "timestamp")))]
+fn test_serialize_hints() {
+    let message = ErrorMessage::new("Connection refused")
+        .with_hint("check that the server is running")
+        .context("Failed to sync")
+        .with_hint("run with `--offline`");
+    let json = serde_json::to_string(&message)
+        .expect("Conversion to json failed");
+
+    println!("test_serialize_hints():\n{}\n", json);
+    assert_eq!(json, r#"{"message":"Failed to sync","hints":["run with `--offline`"],"cause":{"message":"Connection refused","hints":["check that the server is running"],"cause":null}}"#);
+    assert_eq!(message.to_json_string(), json);
+}
+
+#[test]
+#[cfg(feature = "location")]
+fn test_location() {
+    let line = line!() + 1;
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound)).with_err_context("Failed to read file");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let message = message.context("Failed to load configuration");
+
+    let outer = message.location().copied().expect("The outer frame has a location");
+    let inner = message.cause().and_then(|cause| cause.downcast_ref::<ErrorMessage>()).and_then(ErrorMessage::location).copied().expect("The inner frame has a location");
+    println!("test_location():\n{}\n{}\n", outer, inner);
+    assert_eq!(inner.file(), file!());
+    assert_eq!(inner.line(), line);
+    assert_eq!(outer.file(), file!());
+    assert_eq!(outer.line(), line + 2);
+    assert_ne!(inner.line(), outer.line());
+
+    let line = line!() + 1;
+    let message = None::<()>.with_dyn_err_context(|| "Value is missing".to_string()).expect_err("Created an error and didn't get an error");
+    assert_eq!(message.location().map(|location| (location.file(), location.line())), Some((file!(), line)));
+    // With the backtrace feature, the backtrace follows the chain
+    assert!(message.display_verbosity(2).to_string().starts_with(&format!("Value is missing (at {}:{line}:30)", file!())));
+}
+
+/// Asserts, that every frame of the chain was created on the given line of this file
+#[cfg(feature = "location")]
+fn assert_created_at(message: &ErrorMessage, line: u32) {
+    let mut frame = Some(message);
+    while let Some(error) = frame {
+        let location = error.location().expect("Every frame has a location");
+        assert_eq!((location.file(), location.line()), (file!(), line), "Wrong location of {:?}", error.message());
+        frame = error.cause().and_then(|cause| cause.downcast_ref::<ErrorMessage>());
+    }
+}
+
+#[test]
+#[cfg(feature = "location")]
+fn test_location_extension_traits() {
+    use crate::{JoinErrors, LockErrors, SendErrors, StringWithContext};
+
+    let line = line!() + 1;
+    let message = Err::<(), _>("boom").with_err_context("Failed to run the script").expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+    let line = line!() + 1;
+    let message = Err::<(), _>("boom".to_string()).with_dyn_err_context(|| "Failed to run the script").expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    drop(receiver);
+    let line = line!() + 1;
+    let message = sender.send(1).with_send_err_context("Failed to queue the job").expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+    let line = line!() + 1;
+    let message = sender.send(1).with_dyn_send_err_context(|| "Failed to queue the job").expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+    let line = line!() + 1;
+    let (message, _) = sender.send(1).with_send_err_context_and_value("Failed to queue the job").expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+
+    let state = std::sync::RwLock::new(0);
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = state.write();
+        panic!("Poisoning the lock");
+    });
+    let line = line!() + 1;
+    let message = state.read().with_lock_err_context("Failed to read the state").expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+    assert_eq!(message.to_string(), "Failed to read the state\n  caused by: lock poisoned");
+    let line = line!() + 1;
+    let message = state.try_write().with_dyn_lock_err_context(|| "Failed to write the state").expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+
+    let worker = std::thread::spawn(|| panic!("Disk is full"));
+    let result = worker.join();
+    let line = line!() + 1;
+    let message = result.join_with_err_context("Worker failed").expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+
+    let line = line!() + 1;
+    let message = crate::catch_unwind_with_context("Failed to parse the config", || panic!("Unexpected token")).expect_err("Created an error and didn't get an error");
+    assert_created_at(&message, line);
+
+    #[cfg(unix)]
+    {
+        use crate::OsStringErrors;
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let value = OsStr::from_bytes(b"fo\x80o");
+        let line = line!() + 1;
+        let message = value.with_utf8_err_context("Failed to read $HOME").expect_err("Created an error and didn't get an error");
+        assert_created_at(&message, line);
+        let line = line!() + 1;
+        let message = value.to_os_string().into_string().with_utf8_err_context("Failed to read $HOME").expect_err("Created an error and didn't get an error");
+        assert_created_at(&message, line);
+    }
+
+    #[cfg(feature = "anyhow")]
+    {
+        use crate::AnyhowWithContext;
+
+        let error = anyhow::anyhow!("Connection refused").context("Failed to sync");
+        let line = line!() + 1;
+        let message = Err::<(), _>(error).with_err_context("Failed to start").expect_err("Created an error and didn't get an error");
+        assert_created_at(&message, line);
+        let line = line!() + 1;
+        let message = Err::<(), _>(anyhow::anyhow!("Connection refused")).with_dyn_err_context(|| "Failed to start").expect_err("Created an error and didn't get an error");
+        assert_created_at(&message, line);
+    }
+
+    #[cfg(feature = "eyre")]
+    {
+        use crate::EyreWithContext;
+
+        let error = eyre::eyre!("Connection refused").wrap_err("Failed to sync");
+        let line = line!() + 1;
+        let message = Err::<(), _>(error).with_err_context("Failed to start").expect_err("Created an error and didn't get an error");
+        assert_created_at(&message, line);
+        let line = line!() + 1;
+        let message = Err::<(), _>(eyre::eyre!("Connection refused")).with_dyn_err_context(|| "Failed to start").expect_err("Created an error and didn't get an error");
+        assert_created_at(&message, line);
+    }
+
+    let line = line!() + 1;
+    let messages = [
+        crate::ensure(false, "Too many retries"),
+        crate::ensure_dyn(false, || "Too many retries"),
+        crate::ensure_eq(2, 1, "Unsupported version"),
+        crate::ensure_dyn_eq(2, 1, || "Unsupported version"),
+        crate::ensure_ne(1, 1, "Versions must differ"),
+        crate::ensure_dyn_ne(1, 1, || "Versions must differ"),
+    ];
+    for (offset, message) in (1..).zip(messages) {
+        assert_created_at(&message.expect_err("Created an error and didn't get an error"), line + offset);
+    }
+
+    let error = crate::ContextError::from(io::Error::from(ErrorKind::NotFound)).context("Failed to read file").context("Failed to load configuration");
+    let line = line!() + 1;
+    let message = ErrorMessage::from(error);
+    assert_created_at(&message, line);
+
+    #[cfg(feature = "boolean_errors")]
+    {
+        use crate::BooleanErrors;
+
+        let line = line!() + 1;
+        let messages = [
+            false.error_if_false("Condition failed"),
+            true.error_if_true("Condition held"),
+            false.error_dyn_if_false(|| "Condition failed"),
+            true.error_dyn_if_true(|| "Condition held"),
+        ];
+        for (offset, message) in (1..).zip(messages) {
+            assert_created_at(&message.expect_err("Created an error and didn't get an error"), line + offset);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "backtrace")]
+fn test_backtrace() {
+    // Backtrace::capture reads these once per process, so this test checks whatever the test run was started with
+    let enabled = std::env::var("RUST_LIB_BACKTRACE").or_else(|_| std::env::var("RUST_BACKTRACE")).is_ok_and(|value| value != "0");
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    assert_eq!(message.backtrace().is_some(), enabled);
+    // Only the innermost ErrorMessage captures a backtrace
+    assert!(message.metadata.as_ref().and_then(|metadata| metadata.backtrace.as_ref()).is_none());
+    let verbose = message.display_verbosity(2).to_string();
+    println!("test_backtrace():\n{}\n", verbose);
+    assert_eq!(verbose.contains("\n\nstack backtrace:\n"), enabled);
+    assert!(!message.to_string().contains("stack backtrace:"));
+
+    let merged = ErrorMessage::from_errors("Invalid configuration", [ErrorMessage::new("Missing name"), ErrorMessage::new("Missing port")]);
+    assert!(merged.backtrace().is_none());
+    assert_eq!(merged.branches()[0].backtrace().is_some(), enabled);
+}
+
+#[test]
+#[cfg(feature = "timestamp")]
+fn test_timestamp() {
+    use std::time::{Duration, SystemTime};
+
+    let before = SystemTime::now();
+    let inner = ErrorMessage::new("Connection refused");
+    std::thread::sleep(Duration::from_millis(5));
+    let message = inner.context("Failed to sync");
+    let inner = message.cause().and_then(|cause| cause.downcast_ref::<ErrorMessage>()).expect("The cause is an ErrorMessage");
+
+    assert!(before <= inner.created_at());
+    assert!(inner.created_at() < message.created_at());
+    assert!(message.created_at() <= SystemTime::now());
+
+    let mut rendered = String::new();
+    crate::timestamp::write_rfc3339(SystemTime::UNIX_EPOCH + Duration::from_millis(1_709_210_096_789), &mut rendered).unwrap();
+    assert_eq!(rendered, "2024-02-29T12:34:56.789Z");
+    let display = message.display_timestamps().to_string();
+    println!("test_timestamp():\n{}\n", display);
+    assert!(display.starts_with("Failed to sync (20"));
+    assert!(display.contains("Z)\n  caused by: Connection refused (20"));
+}
+
+#[test]
+#[cfg(all(feature = "serde", feature = "timestamp"))]
+fn test_serialize_timestamp() {
+    let message = ErrorMessage::new("Connection refused").context("Failed to sync");
+    let json = serde_json::to_value(&message).expect("Conversion to json failed");
+
+    let outer = json["timestamp"].as_u64().expect("The timestamp is a number");
+    let inner = json["cause"]["timestamp"].as_u64().expect("The timestamp is a number");
+    assert!(inner <= outer);
+    assert_eq!(outer, message.timestamp_millis());
+    assert_eq!(message.to_json_string(), serde_json::to_string(&message).expect("Conversion to json failed"));
+}
+
+#[test]
+#[cfg(feature = "thread_info")]
+fn test_thread_info() {
+    let message = ErrorMessage::new("Connection refused").context("Failed to sync");
+    assert_eq!(message.thread_name(), Some("tests::test_thread_info"));
+    assert_eq!(message.thread_id(), Some(std::thread::current().id()));
+    // Only the innermost frame records the thread
+    assert!(message.metadata.as_ref().and_then(|metadata| metadata.thread.as_ref()).is_none());
+
+    let unnamed = std::thread::spawn(|| ErrorMessage::new("Connection refused").display_thread().to_string());
+    let rendered = unnamed.join().expect("The thread panicked");
+    println!("test_thread_info():\n{}\n", rendered);
+    assert!(rendered.starts_with("Connection refused (on thread ThreadId("));
+}
+
+#[test]
+#[cfg(all(feature = "thread_info", feature = "send"))]
+fn test_thread_info_channel() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let worker = std::thread::Builder::new()
+        .name("worker-3".to_string())
+        .spawn(move || {
+            let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::ConnectionRefused)).with_err_context("Failed to connect");
+            sender.send(result.expect_err("Created an error and didn't get an error")).expect("The receiver is alive");
+        })
+        .expect("Failed to spawn the thread");
+    worker.join().expect("The thread panicked");
+    let message = receiver.recv().expect("The thread sent an error").context("Failed to sync");
+
+    println!("test_thread_info_channel():\n{}\n", message.display_thread());
+    assert_eq!(message.thread_name(), Some("worker-3"));
+    assert_ne!(message.thread_id(), Some(std::thread::current().id()));
+    assert_eq!(message.display_thread().to_string(), r#"Failed to sync
+  caused by: Failed to connect (on thread "worker-3")
+  caused by: Kind(ConnectionRefused)"#);
+}
+
+#[test]
+fn test_error_hook() {
+    use crate::TapWithContext;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // The hook is global and other tests create errors at the same time, so only errors of this test are recorded
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    static TAPPED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    assert!(crate::set_error_hook(|error| {
+        if error.message().starts_with("[tap]") {
+            TAPPED.lock().unwrap().push(error.to_string());
+        }
+        if error.message().starts_with("[hook]") {
+            COUNT.fetch_add(1, Ordering::Relaxed);
+            MESSAGES.lock().unwrap().push(error.message().to_string());
+            // Errors created inside of the hook don't call it again
+            let _ = ErrorMessage::new("[hook] Created by the hook");
+        }
+    }));
+    assert!(!crate::set_error_hook(|_| {}));
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("[hook] Failed to read file")
+        .with_err_context("[hook] Failed to load configuration")
+        .with_err_context("[hook] Failed to start the program");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(COUNT.load(Ordering::Relaxed), 1);
+
+    let _ = message.context("[hook] Failed to run");
+    let _ = ErrorMessage::err::<()>("[hook] Value is missing");
+    let _ = None::<()>.with_err_context("[hook] Option is empty");
+    let _ = ErrorMessage::from_errors("[hook] Invalid configuration", [ErrorMessage::new("[hook] Missing name"), ErrorMessage::new("[hook] Missing port")]);
+    assert_eq!(COUNT.load(Ordering::Relaxed), 5);
+    assert_eq!(
+        *MESSAGES.lock().unwrap(),
+        ["[hook] Failed to read file", "[hook] Value is missing", "[hook] Option is empty", "[hook] Missing name", "[hook] Missing port"]
+    );
+
+    // The chains of tap_err_context are only reported, the original errors are returned untouched
+    let result: Result<(), _> = Err(io::Error::from(ErrorKind::NotFound)).tap_err_context("[tap] Failed to read cache");
+    assert_eq!(result.map_err(|error| error.kind()), Err(ErrorKind::NotFound));
+    let result: Result<(), _> = Err(ErrorKind::PermissionDenied).tap_err_with(|kind| format!("[tap] Failed with {kind:?}"));
+    assert_eq!(result, Err(ErrorKind::PermissionDenied));
+    assert_eq!(Ok::<_, ErrorKind>(42).tap_err_context("[tap] Not reported"), Ok(42));
+    println!("test_error_hook():\n{:#?}\n", TAPPED.lock().unwrap());
+    assert_eq!(
+        *TAPPED.lock().unwrap(),
+        ["[tap] Failed to read cache\n  caused by: Kind(NotFound)", "[tap] Failed with PermissionDenied\n  caused by: PermissionDenied"]
+    );
+}
+
+
+#[cfg(feature = "log")]
+struct CapturingLogger {
+    records: std::sync::Mutex<Vec<(String, log::Level, String)>>,
+}
+
+#[cfg(feature = "log")]
+impl log::Log for CapturingLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.records.lock().unwrap().push((record.target().to_string(), record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+#[cfg(feature = "log")]
+fn test_log() {
+    use crate::LogWithContext;
+    use log::Level;
+
+    static LOGGER: CapturingLogger = CapturingLogger { records: std::sync::Mutex::new(Vec::new()) };
+    log::set_logger(&LOGGER).expect("No other test sets a logger");
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read file")
+        .log_err_context(Level::Warn, "Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to load configuration\n  caused by: Failed to read file\n  caused by: Kind(NotFound)");
+    assert_eq!(Some(42).log_err_context(Level::Error, "Value is missing").ok(), Some(42));
+    None::<()>.log_err_context(Level::Error, "Value is missing").expect_err("Created an error and didn't get an error");
+    message.context("Failed to start the program").log(Level::Debug);
+
+    let path = "cache.json";
+    assert_eq!(Err::<(), _>(io::Error::from(ErrorKind::NotFound)).warn_err_context("Failed to read cache"), None);
+    assert_eq!(Ok::<_, io::Error>(42).warn_err_context("Failed to read cache"), Some(42));
+    assert_eq!(None::<()>.warn_dyn_err_context(|| format!("Missing cache '{path}'")), None);
+    assert_eq!(Err::<(), _>(io::Error::from(ErrorKind::NotFound)).log_err_context_or_none(Level::Info, "Failed to read cache"), None);
+    assert_eq!(Some(42).log_err_context_or_none(Level::Info, "Value is missing"), Some(42));
+
+    let records = LOGGER.records.lock().unwrap();
+    println!("test_log():\n{:#?}\n", records);
+    assert_eq!(
+        *records,
+        [
+            ("errors_with_context".to_string(), Level::Warn, "Failed to load configuration: Failed to read file: Kind(NotFound)".to_string()),
+            ("errors_with_context".to_string(), Level::Error, "Value is missing".to_string()),
+            ("errors_with_context".to_string(), Level::Debug, "Failed to start the program: Failed to load configuration: Failed to read file: Kind(NotFound)".to_string()),
+            ("errors_with_context".to_string(), Level::Warn, "Failed to read cache: Kind(NotFound)".to_string()),
+            ("errors_with_context".to_string(), Level::Warn, "Missing cache 'cache.json'".to_string()),
+            ("errors_with_context".to_string(), Level::Info, "Failed to read cache: Kind(NotFound)".to_string()),
+        ]
+    );
+}
+
+/// The target, the level and the fields of an event
+#[cfg(feature = "tracing")]
+type CollectedEvent = (String, tracing::Level, Vec<(String, String)>);
+
+/// Collects the fields of all events as strings
+#[cfg(feature = "tracing")]
+#[derive(Default)]
+struct CollectingSubscriber {
+    events: std::sync::Mutex<Vec<CollectedEvent>>,
+}
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for CollectingSubscriber {
+    fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        struct Fields(Vec<(String, String)>);
+        impl tracing::field::Visit for Fields {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                self.0.push((field.name().to_string(), format!("{value:?}")));
+            }
+        }
+        let mut fields = Fields(Vec::new());
+        event.record(&mut fields);
+        let metadata = event.metadata();
+        self.events.lock().unwrap().push((metadata.target().to_string(), *metadata.level(), fields.0));
+    }
+
+    fn enter(&self, _span: &tracing::span::Id) {}
+
+    fn exit(&self, _span: &tracing::span::Id) {}
+}
+
+#[test]
+#[cfg(feature = "tracing")]
+fn test_tracing() {
+    use crate::TraceWithContext;
+    use std::sync::Arc;
+
+    let subscriber = Arc::new(CollectingSubscriber::default());
+    let message = tracing::subscriber::with_default(subscriber.clone(), || {
+        let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+            .with_err_context("Failed to read file")
+            .trace_err_context("Failed to load configuration");
+        assert_eq!(Some(42).trace_err_context("Value is missing").ok(), Some(42));
+        let message = result.expect_err("Created an error and didn't get an error");
+        ErrorMessage::new("Connection refused").record();
+        message
+    });
+    assert_eq!(message.to_string(), "Failed to load configuration\n  caused by: Failed to read file\n  caused by: Kind(NotFound)");
+
+    let events = subscriber.events.lock().unwrap();
+    println!("test_tracing():\n{:#?}\n", events);
+    let field = |name: &str, value: &str| (name.to_string(), value.to_string());
+    assert_eq!(
+        *events,
+        [
+            (
+                "errors_with_context".to_string(),
+                tracing::Level::ERROR,
+                vec![
+                    field("message", "Failed to load configuration"),
+                    field("error.chain", "Failed to load configuration: Failed to read file: Kind(NotFound)"),
+                    field("error.root", "Kind(NotFound)"),
+                    field("error.depth", "3"),
+                ]
+            ),
+            (
+                "errors_with_context".to_string(),
+                tracing::Level::ERROR,
+                vec![
+                    field("message", "Connection refused"),
+                    field("error.chain", "Connection refused"),
+                    field("error.root", "Connection refused"),
+                    field("error.depth", "1"),
+                ]
+            ),
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "spantrace")]
+fn test_span_trace() {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let subscriber = tracing_subscriber::registry().with(tracing_error::ErrorLayer::default());
+    let message = tracing::subscriber::with_default(subscriber, || {
+        let _request = tracing::info_span!("request", id = 7).entered();
+        let _load = tracing::info_span!("load_config", path = "/etc/app.toml").entered();
+        let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+            .with_err_context("Failed to read file")
+            .with_err_context("Failed to load configuration");
+        result.expect_err("Created an error and didn't get an error")
+    });
+
+    let verbose = message.display_verbosity(2).to_string();
+    println!("test_span_trace():\n{}\n", verbose);
+    assert!(message.span_trace().is_some());
+    // Only the innermost ErrorMessage captures a span trace
+    assert!(message.metadata.as_ref().and_then(|metadata| metadata.span_trace.as_ref()).is_none());
+    let span_trace = verbose.split_once("\n\nspan trace:\n").expect("The span trace is shown after the chain").1;
+    let request = span_trace.find("request").expect("The outer span is part of the span trace");
+    let load = span_trace.find("load_config").expect("The inner span is part of the span trace");
+    assert!(load < request);
+    assert!(span_trace.contains("id=7"));
+    assert!(!message.to_string().contains("span trace:"));
+}
+
+#[test]
+fn test_panic_hook() {
+    use crate::panic::write_panic;
+    use std::panic::Location;
+
+    let location = Location::caller();
+    let mut report = String::new();
+    write_panic(Some("main"), &"called `Option::unwrap()` on a `None` value", Some(location), &mut report).unwrap();
+    println!("test_panic_hook():\n{}\n", report);
+    assert_eq!(report, format!("Thread 'main' panicked (at {}:{}:{})
+  caused by: called `Option::unwrap()` on a `None` value", location.file(), location.line(), location.column()));
+
+    let mut report = String::new();
+    write_panic(None, &String::from("Invalid state:\nqueue is empty"), None, &mut report).unwrap();
+    assert_eq!(report, "Thread panicked\n  caused by: Invalid state:\n             queue is empty");
+
+    // Only ErrorMessages, that are Send, can be a panic payload
+    #[cfg(feature = "send")]
+    {
+        let mut report = String::new();
+        write_panic(Some("worker"), &ErrorMessage::new("Connection refused").context("Failed to sync"), None, &mut report).unwrap();
+        assert_eq!(report, "Thread 'worker' panicked\n  caused by: Failed to sync\n  caused by: Connection refused");
+    }
+
+    let mut report = String::new();
+    write_panic(Some("worker"), &42, None, &mut report).unwrap();
+    assert_eq!(report, "Thread 'worker' panicked\n  caused by: Box<dyn Any>");
+}
+
+#[test]
+fn test_catch_unwind_with_context() {
+    use crate::{catch_unwind_with_context, try_catch_unwind_with_context};
+
+    let message = catch_unwind_with_context("Failed to parse the config", || panic!("Unexpected token '{}'", '}'))
+        .expect_err("Created an error and didn't get an error");
+    println!("test_catch_unwind_with_context():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to parse the config\n  caused by: Unexpected token '}'");
+
+    let message = catch_unwind_with_context("Failed to parse the config", || std::panic::panic_any(42))
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to parse the config\n  caused by: panic with non-string payload");
+
+    assert_eq!(catch_unwind_with_context("Failed to parse the config", || 42).ok(), Some(42));
+
+    let message = try_catch_unwind_with_context("Failed to parse the config", || ErrorMessage::err::<()>("Unexpected token"))
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to parse the config\n  caused by: Unexpected token");
+    let message = try_catch_unwind_with_context("Failed to parse the config", || -> Result<(), ErrorMessage> { panic!("Unexpected token") })
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to parse the config\n  caused by: Unexpected token");
+    assert_eq!(try_catch_unwind_with_context("Failed to parse the config", || Ok(42)).ok(), Some(42));
+}
+
+#[cfg(feature = "sentry")]
+#[test]
+fn test_to_sentry_event() {
+    let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+        .with_err_context("Failed to open config")
+        .map_err(|error| error.with_detail("path", "/etc/app.toml").with_detail("attempt", 1))
+        .map_err(|error| error.context("Failed to start").with_detail("attempt", 2));
+    let message = result.expect_err("Created an error and didn't get an error");
+    let event = message.to_sentry_event();
+    println!("test_to_sentry_event():\n{:#?}\n", event.exception.values);
+
+    let exceptions: Vec<_> =
+        event.exception.values.iter().map(|exception| (exception.ty.as_str(), exception.value.as_deref())).collect();
+    assert_eq!(
+        exceptions,
+        [
+            (std::any::type_name::<io::Error>(), Some("Kind(NotFound)")),
+            ("ErrorMessage", Some("Failed to open config")),
+            ("ErrorMessage", Some("Failed to start")),
+        ]
+    );
+    assert_eq!(event.level, sentry::Level::Error);
+    assert_eq!(event.extra.len(), 2);
+    assert_eq!(event.extra["path"], "/etc/app.toml");
+    assert_eq!(event.extra["attempt"], "2");
+}
+
+#[cfg(feature = "miette")]
+#[test]
+fn test_miette_diagnostic() {
+    use miette::{Diagnostic, NarratableReportHandler};
+
+    let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+        .with_err_context("Failed to open config")
+        .map_err(|error| error.with_hint("run `myapp init` to create it").with_id("CFG001"))
+        .with_err_context("Failed to start");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let mut report = String::new();
+    NarratableReportHandler::new().render_report(&mut report, &message).expect("Failed to render report");
+    println!("test_miette_diagnostic():\n{}\n", report);
+
+    for frame in ["Failed to start", "Failed to open config", "Kind(NotFound)"] {
+        assert!(report.contains(frame), "{frame:?} is missing in the report");
+    }
+    assert_eq!(message.code().map(|code| code.to_string()).as_deref(), Some("CFG001"));
+    assert_eq!(message.help().map(|help| help.to_string()).as_deref(), Some("run `myapp init` to create it"));
+
+    let message = ErrorMessage::new("Connection refused").with_exit_code(75);
+    assert_eq!(message.code().map(|code| code.to_string()).as_deref(), Some("75"));
+    assert!(message.help().is_none());
+    assert!(ErrorMessage::new("Connection refused").code().is_none());
+
+    let message = ErrorMessage::new("Quota exceeded").with_code("E1042").context("Failed to upload file").with_id("UPL001");
+    assert_eq!(message.code().map(|code| code.to_string()).as_deref(), Some("E1042"));
+}
+
+#[cfg(feature = "send")]
+#[test]
+fn test_io_error_roundtrip() {
+    let result: Result<Infallible, _> =
+        Err(io::Error::from(io::ErrorKind::NotFound)).with_err_context("Failed to open config").with_err_context("Failed to start");
+    let message = result.expect_err("Created an error and didn't get an error");
+    let expected = message.to_string();
+
+    let error = io::Error::from(message);
+    println!("test_io_error_roundtrip():\n{}\n", error);
+    assert_eq!(error.kind(), io::ErrorKind::Other);
+    assert_eq!(error.to_string(), expected);
+    let inner = error.get_ref().and_then(|inner| inner.downcast_ref::<crate::IoErrorMessage>()).expect("The inner error is an IoErrorMessage");
+    assert_eq!(inner.lock().to_string(), expected);
+
+    let message = ErrorMessage::from_io_roundtrip(error);
+    assert_eq!(message.to_string(), expected);
+    assert_eq!(message.chain().count(), 3);
+
+    let inner = io::Error::from(message).into_inner().expect("The io::Error has an inner error");
+    let message = inner.downcast::<crate::IoErrorMessage>().expect("The inner error is an IoErrorMessage").into_inner();
+    assert_eq!(message.to_string(), expected);
+}
+
+#[test]
+fn test_from_io_roundtrip_foreign() {
+    let message = ErrorMessage::from_io_roundtrip(io::Error::from(io::ErrorKind::NotFound));
+    assert_eq!(message.to_string(), "entity not found\n  caused by: Kind(NotFound)");
+}
+
+#[test]
+fn test_from_string() {
+    use std::error::Error;
+
+    fn check(value: u8) -> Result<u8, ErrorMessage> {
+        match value {
+            0 => Err("Value is zero")?,
+            1 => Err(format!("Value {value} is too small"))?,
+            value => Ok(value),
+        }
+    }
+    let message = check(0).expect_err("Created an error and didn't get an error");
+    println!("test_from_string():\n{}\n", message);
+    assert_eq!(message.to_string(), "Value is zero");
+    assert!(message.source().is_none());
+    let message = check(1).expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Value 1 is too small");
+    assert!(message.source().is_none());
+    assert_eq!(check(2).ok(), Some(2));
+}
+
+#[cfg(feature = "std_conversions")]
+#[test]
+fn test_std_conversions() {
+    fn parse(bytes: Vec<u8>) -> Result<u8, ErrorMessage> {
+        let text = String::from_utf8(bytes)?;
+        let number: i64 = text.parse()?;
+        Ok(u8::try_from(number)?)
+    }
+    let message = parse(vec![0xff]).expect_err("Created an error and didn't get an error");
+    println!("test_std_conversions():\n{}\n", message);
+    assert_eq!(message.message(), "invalid utf-8 sequence of 1 bytes from index 0");
+    assert!(format!("{message}").contains("caused by: FromUtf8Error"));
+    let message = parse(b"four".to_vec()).expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "invalid digit found in string\n  caused by: ParseIntError { kind: InvalidDigit }");
+    let message = parse(b"256".to_vec()).expect_err("Created an error and didn't get an error");
+    assert!(message.to_string().contains("caused by: TryFromIntError"));
+    assert_eq!(parse(b"42".to_vec()).ok(), Some(42));
+}
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn test_into_anyhow() {
+    let result: Result<Infallible, _> =
+        Err(io::Error::from(io::ErrorKind::NotFound)).with_err_context("Failed to open config").with_err_context("Failed to start");
+    let error = result.expect_err("Created an error and didn't get an error").into_anyhow();
+    println!("test_into_anyhow():\n{:?}\n", error);
+
+    let chain: Vec<_> = error.chain().map(|error| error.to_string()).collect();
+    assert_eq!(chain, ["Failed to start", "Failed to open config", "Kind(NotFound)"]);
+    assert_eq!(format!("{error:#}"), "Failed to start: Failed to open config: Kind(NotFound)");
+    assert_eq!(error.root_cause().to_string(), "Kind(NotFound)");
+    assert!(format!("{error:?}").starts_with("Failed to start\n\nCaused by:\n    0: Failed to open config\n    1: Kind(NotFound)"));
+}
+
+#[cfg(feature = "anyhow")]
+#[test]
+fn test_anyhow_with_context() {
+    use crate::AnyhowWithContext;
+    use anyhow::Context;
+
+    let result: anyhow::Result<Infallible> =
+        Err(io::Error::from(io::ErrorKind::NotFound)).context("Failed to open config").context("Failed to load settings");
+    let message = result.with_err_context("Failed to start").expect_err("Created an error and didn't get an error");
+    println!("test_anyhow_with_context():\n{}\n", message);
+
+    let expected: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+        .with_err_context("Failed to open config")
+        .with_err_context("Failed to load settings")
+        .with_err_context("Failed to start");
+    let expected = expected.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), expected.to_string());
+    assert_eq!(message.chain().count(), 4);
+
+    let result: anyhow::Result<()> = Err(anyhow::anyhow!("Connection refused"));
+    let message = result.with_err_context_id("Failed to sync", "SYNC").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "[SYNC] Failed to sync\n  caused by: Connection refused\n  caused by: \"Connection refused\"");
+}
+
+#[cfg(feature = "eyre")]
+#[test]
+fn test_eyre_roundtrip() {
+    use crate::EyreWithContext;
+    use eyre::WrapErr;
+
+    let result: eyre::Result<Infallible> =
+        Err(io::Error::from(io::ErrorKind::NotFound)).wrap_err("Failed to open config").wrap_err("Failed to load settings");
+    let message = result.with_err_context("Failed to start").expect_err("Created an error and didn't get an error");
+    println!("test_eyre_roundtrip():\n{}\n", message);
+    assert_eq!(
+        message.to_string(),
+        "Failed to start\n  caused by: Failed to load settings\n  caused by: Failed to open config\n  caused by: Kind(NotFound)"
+    );
+
+    let report = message.into_eyre();
+    let chain: Vec<_> = report.chain().map(|error| error.to_string()).collect();
+    assert_eq!(chain, ["Failed to start", "Failed to load settings", "Failed to open config", "Kind(NotFound)"]);
+
+    let message = ErrorMessage::from_eyre(report);
+    assert_eq!(
+        message.to_string(),
+        "Failed to start\n  caused by: Failed to load settings\n  caused by: Failed to open config\n  caused by: \"Kind(NotFound)\""
+    );
+}
+
+#[test]
+fn test_boxed_with_context() {
+    use crate::BoxedWithContext;
+    use std::error::Error;
+
+    fn open_config() -> Result<(), Box<dyn Error + Send + Sync>> {
+        Err(Box::new(io::Error::from(io::ErrorKind::NotFound)))
+    }
+    let message = open_config().with_err_context("Failed to open config").expect_err("Created an error and didn't get an error");
+    println!("test_boxed_with_context():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to open config\n  caused by: Kind(NotFound)");
+    let leaf = message.source().expect("Missing leaf");
+    assert_eq!(leaf.downcast_ref::<io::Error>().map(io::Error::kind), Some(ErrorKind::NotFound));
+
+    let result: Result<(), Box<dyn Error + Send>> = Err(Box::new(io::Error::from(io::ErrorKind::NotFound)));
+    let message = result.with_err_context_id("Failed to open config", "CFG001").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "[CFG001] Failed to open config\n  caused by: Kind(NotFound)");
+
+    #[cfg(not(feature = "send"))]
+    {
+        let result: Result<(), Box<dyn Error>> = Err(Box::new(io::Error::from(io::ErrorKind::NotFound)));
+        let message = result
+            .with_dyn_err_context(|| format!("Failed to open {}", "config"))
+            .expect_err("Created an error and didn't get an error");
+        assert_eq!(message.to_string(), "Failed to open config\n  caused by: Kind(NotFound)");
+        assert!(message.source().and_then(|leaf| leaf.downcast_ref::<io::Error>()).is_some());
+    }
+}
+
+#[test]
+fn test_string_with_context() {
+    use crate::StringWithContext;
+
+    let result: Result<(), String> = Err("boom".to_string());
+    let message = result.with_err_context("doing x").expect_err("Created an error and didn't get an error");
+    println!("test_string_with_context():\n{}\n", message);
+    assert_eq!(message.to_string(), "doing x\n  caused by: boom");
+    assert_eq!(message.chain().count(), 2);
+
+    let result: Result<(), &str> = Err("Value \"x\" is unknown");
+    let message = result.with_err_context_id("Failed to parse", "PARSE").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "[PARSE] Failed to parse\n  caused by: Value \"x\" is unknown");
+
+    let result: Result<u8, &str> = Ok(1);
+    assert_eq!(result.with_dyn_err_context(|| "Failed to parse".to_string()).ok(), Some(1));
+}
+
+#[cfg(feature = "display_errors")]
+#[test]
+fn test_with_display_context() {
+    use crate::WithDisplayContext;
+    use std::fmt::{Display, Formatter};
+
+    struct DeviceError {
+        status: i32,
+    }
+    impl Display for DeviceError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Device returned status {}", self.status)
+        }
+    }
+
+    let result: Result<(), _> = Err(DeviceError { status: -5 });
+    let message = result.with_display_err_context("Failed to open the camera").expect_err("Created an error and didn't get an error");
+    println!("test_with_display_context():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to open the camera\n  caused by: Device returned status -5");
+    assert_eq!(message.chain().count(), 2);
+
+    let result: Result<(), _> = Err(DeviceError { status: 3 });
+    let message = result
+        .with_dyn_display_err_context(|| format!("Failed to open camera {}", 2))
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to open camera 2\n  caused by: Device returned status 3");
+}
+
+#[test]
+fn test_with_err_context_stringify() {
+    use crate::StringifyWithContext;
+    use std::fmt::{Display, Formatter};
+    use std::rc::Rc;
+
+    /// Neither `Send` nor `'static`
+    #[derive(Debug)]
+    struct LocalError<'a> {
+        input: &'a str,
+        owner: Rc<String>,
+    }
+    impl Display for LocalError<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "'{}' is not valid for {}", self.input, self.owner)
+        }
+    }
+    impl std::error::Error for LocalError<'_> {}
+
+    let input = String::from("four");
+    let result: Result<(), _> = Err(LocalError { input: &input, owner: Rc::new("the port".to_string()) });
+    let message = result.with_err_context_stringify("Failed to parse the config").expect_err("Created an error and didn't get an error");
+    println!("test_with_err_context_stringify():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to parse the config\n  caused by: 'four' is not valid for the port");
+    assert_eq!(message.chain().count(), 2);
+
+    let result: Result<(), _> = Err(LocalError { input: &input, owner: Rc::new("the host".to_string()) });
+    let message = result
+        .with_dyn_err_context_stringify(|| format!("Failed to parse {}", "the config"))
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to parse the config\n  caused by: 'four' is not valid for the host");
+}
+
+#[test]
+fn test_lock_errors() {
+    use crate::LockErrors;
+    use std::sync::{Arc, Mutex, RwLock};
+
+    let state = Arc::new(Mutex::new(0));
+    let poisoner = Arc::clone(&state);
+    std::thread::spawn(move || {
+        let _guard = poisoner.lock().unwrap();
+        panic!("Poisoning the mutex");
+    })
+    .join()
+    .expect_err("The thread didn't panic");
+
+    let message = state.lock().with_lock_err_context("Failed to lock the state").expect_err("Created an error and didn't get an error");
+    println!("test_lock_errors():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to lock the state\n  caused by: lock poisoned");
+    let message = state
+        .try_lock()
+        .with_dyn_lock_err_context(|| format!("Failed to lock {}", "the state"))
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to lock the state\n  caused by: lock poisoned");
+
+    let lock = RwLock::new(0);
+    let _reader = lock.read().with_lock_err_context("Failed to read the state").expect("Lock isn't poisoned");
+    let message = lock.try_write().with_lock_err_context("Failed to write the state").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to write the state\n  caused by: lock would block");
+}
+
+#[test]
+fn test_send_errors() {
+    use crate::SendErrors;
+    use std::sync::mpsc;
+
+    /// Doesn't implement Debug, so `SendError<Job>` isn't an `Error`
+    struct Job {
+        id: u32,
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    drop(receiver);
+    let message = sender.send(Job { id: 1 }).with_send_err_context("Failed to queue job 1").expect_err("Created an error and didn't get an error");
+    println!("test_send_errors():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to queue job 1\n  caused by: channel closed");
+
+    let (sender, receiver) = mpsc::sync_channel(1);
+    sender.try_send(Job { id: 1 }).with_send_err_context("Failed to queue job 1").expect("Channel has room for one job");
+    let message = sender
+        .try_send(Job { id: 2 })
+        .with_dyn_send_err_context(|| format!("Failed to queue job {}", 2))
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to queue job 2\n  caused by: channel full");
+
+    let (message, job) =
+        sender.try_send(Job { id: 3 }).with_send_err_context_and_value("Failed to queue job 3").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to queue job 3\n  caused by: channel full");
+    assert_eq!(job.id, 3);
+    drop(receiver);
+    let (message, job) =
+        sender.try_send(job).with_send_err_context_and_value("Failed to queue job 3").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to queue job 3\n  caused by: channel closed");
+    assert_eq!(job.id, 3);
+}
+
+#[cfg(feature = "crossbeam")]
+#[test]
+fn test_send_errors_crossbeam() {
+    use crate::SendErrors;
+    use std::time::Duration;
+
+    let (sender, receiver) = crossbeam_channel::bounded(1);
+    sender.send(1).with_send_err_context("Failed to queue job 1").expect("Channel has room for one job");
+    let message = sender.try_send(2).with_send_err_context("Failed to queue job 2").expect_err("Created an error and didn't get an error");
+    println!("test_send_errors_crossbeam():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to queue job 2\n  caused by: channel full");
+    let (message, job) = sender
+        .send_timeout(3, Duration::from_millis(1))
+        .with_send_err_context_and_value("Failed to queue job 3")
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to queue job 3\n  caused by: send timed out");
+    assert_eq!(job, 3);
+    drop(receiver);
+    let message = sender.send(4).with_send_err_context("Failed to queue job 4").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to queue job 4\n  caused by: channel closed");
+}
+
+#[test]
+fn test_join_errors() {
+    use crate::JoinErrors;
+
+    let worker = std::thread::spawn(|| -> u8 { panic!("Disk is {}", "full") });
+    let message = worker.join().join_with_err_context("Worker failed").expect_err("Created an error and didn't get an error");
+    println!("test_join_errors():\n{}\n", message);
+    assert_eq!(message.to_string(), "Worker failed\n  caused by: Disk is full");
+
+    let worker = std::thread::spawn(|| -> u8 { std::panic::panic_any(42) });
+    let message = worker.join().join_with_err_context("Worker failed").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Worker failed\n  caused by: thread panicked with non-string payload");
+    assert_eq!(std::thread::spawn(|| 42).join().join_with_err_context("Worker failed").ok(), Some(42));
+}
+
+#[cfg(feature = "send")]
+#[test]
+fn test_try_join_errors() {
+    use crate::{JoinErrors, TryJoinErrors};
+
+    let worker = std::thread::spawn(|| ErrorMessage::err::<u8>("Disk is full").map_err(|error| error.context("Failed to write")));
+    let message = worker.join().try_join_with_err_context("Worker failed").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Worker failed\n  caused by: Failed to write\n  caused by: Disk is full");
+
+    let worker = std::thread::spawn(|| -> Result<u8, ErrorMessage> { panic!("Disk is full") });
+    let message = worker.join().try_join_with_err_context("Worker failed").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Worker failed\n  caused by: Disk is full");
+
+    let worker = std::thread::spawn(|| std::panic::panic_any(ErrorMessage::new("Disk is full").context("Failed to write")));
+    let message = worker.join().join_with_err_context("Worker failed").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Worker failed\n  caused by: Failed to write\n  caused by: Disk is full");
+
+    assert_eq!(std::thread::spawn(|| Ok(42)).join().try_join_with_err_context("Worker failed").ok(), Some(42));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_os_string_errors() {
+    use crate::OsStringErrors;
+    use std::ffi::{OsStr, OsString};
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+    let value = OsString::from_vec(b"fo\xffo".to_vec());
+    let message = value.clone().into_string().with_utf8_err_context("Failed to read $USER").expect_err("Created an error and didn't get an error");
+    println!("test_os_string_errors():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to read $USER\n  caused by: value is not valid UTF-8: \"fo\u{FFFD}o\"");
+
+    let message = value.as_os_str().with_utf8_err_context("Failed to read $USER").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to read $USER\n  caused by: value is not valid UTF-8: \"fo\u{FFFD}o\"");
+
+    assert_eq!(OsStr::from_bytes(b"foo").with_utf8_err_context("Failed to read $USER").ok(), Some("foo"));
+    assert_eq!(OsString::from("foo").into_string().with_utf8_err_context("Failed to read $USER").ok().as_deref(), Some("foo"));
+}
+
+#[cfg(all(feature = "process", unix))]
+#[test]
+fn test_process_errors() {
+    use crate::{run_with_context, ProcessErrors};
+    use std::process::Command;
+
+    let status = Command::new("false").status().expect("Failed to run false");
+    let message = status.success_or_err_context("Failed to check the backup").expect_err("Created an error and didn't get an error");
+    println!("test_process_errors():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to check the backup\n  caused by: process exited with code 1");
+
+    let message = run_with_context("sh", ["-c", "echo 'Device is busy' >&2; exit 32"]).expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to run command 'sh'\n  caused by: process exited with code 32\n    stderr: Device is busy");
+
+    let message = run_with_context("sh", ["-c", "printf 'x%.0s' $(seq 3000) >&2; exit 1"]).expect_err("Created an error and didn't get an error");
+    let process = message.root_cause().downcast_ref::<ErrorMessage>().expect("Missing process frame");
+    let stderr = &process.details()[0].1;
+    assert_eq!(stderr.len(), "[...] ".len() + 2048);
+    let message = run_with_context("sh", ["-c", "kill -9 $$"]).expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to run command 'sh'\n  caused by: process was killed by signal 9");
+
+    let message = run_with_context("does-not-exist", ["--help"]).expect_err("Created an error and didn't get an error");
+    assert!(message.to_string().starts_with("Failed to run command 'does-not-exist'\n  caused by: Os { code: 2, kind: NotFound"));
+
+    assert_eq!(run_with_context("echo", ["Hello"]).ok().as_deref(), Some("Hello\n"));
+}
+
+#[test]
+fn test_poll_with_context() {
+    use crate::PollWithContext;
+    use std::cell::Cell;
+    use std::task::Poll;
+
+    let built = Cell::new(0);
+    let context = || {
+        built.set(built.get() + 1);
+        "Failed to read the frame header".to_string()
+    };
+
+    let poll: Poll<Result<u8, io::Error>> = Poll::Pending;
+    assert!(poll.with_dyn_err_context(context).is_pending());
+    let poll: Poll<Result<u8, io::Error>> = Poll::Ready(Ok(4));
+    assert_eq!(poll.with_dyn_err_context(context).map(Result::ok), Poll::Ready(Some(4)));
+    assert_eq!(built.get(), 0);
+
+    let poll: Poll<Result<u8, io::Error>> = Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+    let Poll::Ready(result) = poll.with_dyn_err_context(context) else { panic!("Poll became pending") };
+    let message = result.expect_err("Created an error and didn't get an error");
+    println!("test_poll_with_context():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to read the frame header\n  caused by: Kind(UnexpectedEof)");
+    assert_eq!(built.get(), 1);
+
+    let poll: Poll<Result<u8, io::Error>> = Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+    let Poll::Ready(result) = poll.with_err_context_id("Failed to read the frame header", "FRAME") else { panic!("Poll became pending") };
+    assert_eq!(result.expect_err("Created an error and didn't get an error").id(), Some("FRAME"));
+}
+
+#[test]
+fn test_flatten_with_context() {
+    use crate::FlattenWithContext;
+
+    let result: Result<Result<u8, ErrorMessage>, io::Error> = Err(io::ErrorKind::BrokenPipe.into());
+    let message = result.flatten_with_context("Failed to call the worker").expect_err("Created an error and didn't get an error");
+    println!("test_flatten_with_context():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to call the worker\n  caused by: Kind(BrokenPipe)");
+
+    let result: Result<Result<u8, ErrorMessage>, io::Error> = Ok(ErrorMessage::err("Disk is full"));
+    let message = result.flatten_with_context("Failed to call the worker").expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to call the worker\n  caused by: Disk is full");
+    assert_eq!(message.chain().count(), 2);
+
+    let result: Result<Result<u8, io::Error>, ErrorMessage> = Ok(Err(io::ErrorKind::NotFound.into()));
+    let message =
+        result.flatten_with_dyn_context(|| format!("Failed to call worker {}", 2)).expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to call worker 2\n  caused by: Kind(NotFound)");
+
+    let result: Result<Result<u8, ErrorMessage>, io::Error> = Ok(Ok(4));
+    assert_eq!(result.flatten_with_context("Failed to call the worker").ok(), Some(4));
+}
+
+#[cfg(feature = "axum")]
+#[test]
+fn test_axum_into_response() {
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    async fn load_user() -> Result<String, ErrorMessage> {
+        Err(io::Error::from(io::ErrorKind::ConnectionRefused)).with_err_context("Failed to query the database").with_err_context("Failed to load the user")
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread().build().expect("Failed to build runtime");
+    let response = runtime.block_on(async {
+        let app = Router::new().route("/user", get(load_user));
+        let request = Request::builder().uri("/user").body(Body::empty()).expect("Failed to build request");
+        app.oneshot(request).await.expect("Router is infallible")
+    });
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    let body = runtime.block_on(axum::body::to_bytes(response.into_body(), usize::MAX)).expect("Failed to read body");
+    let body = String::from_utf8(body.to_vec()).expect("Body isn't UTF-8");
+    println!("test_axum_into_response():\n{}\n", body);
+    assert_eq!(
+        body,
+        r#"{"error":"Failed to load the user","chain":["Failed to load the user","Failed to query the database","Kind(ConnectionRefused)"]}"#
+    );
+    let json: serde_json::Value = serde_json::from_str(&body).expect("Body isn't JSON");
+    assert_eq!(json["chain"].as_array().map(Vec::len), Some(3));
+
+    let response = ErrorMessage::new("No user with id 42").with_status(StatusCode::NOT_FOUND).context("Failed to load the user").into_response();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[cfg(feature = "actix")]
+#[test]
+fn test_actix_response_error() {
+    use actix_web::http::{header, StatusCode};
+    use actix_web::{test, web, App, HttpResponse};
+
+    async fn load_user() -> Result<HttpResponse, ErrorMessage> {
+        let name: String = Err(io::Error::from(io::ErrorKind::ConnectionRefused))
+            .with_err_context("Failed to query the database")
+            .with_err_context("Failed to load the user")?;
+        Ok(HttpResponse::Ok().body(name))
+    }
+
+    actix_web::rt::System::new().block_on(async {
+        let app = test::init_service(App::new().route("/user", web::get().to(load_user))).await;
+        let response = test::call_service(&app, test::TestRequest::get().uri("/user").to_request()).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.headers().get(header::CONTENT_TYPE).map(|value| value.as_bytes()), Some(&b"application/json"[..]));
+        let body = String::from_utf8(test::read_body(response).await.to_vec()).expect("Body isn't UTF-8");
+        println!("test_actix_response_error():\n{}\n", body);
+        assert_eq!(
+            body,
+            r#"{"error":"Failed to load the user","chain":["Failed to load the user","Failed to query the database","Kind(ConnectionRefused)"]}"#
+        );
+    });
+
+    let error = ErrorMessage::new("No user with id 42").with_status(http::StatusCode::NOT_FOUND).context("Failed to load the user");
+    assert_eq!(actix_web::ResponseError::status_code(&error), StatusCode::NOT_FOUND);
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn test_status() {
+    use http::StatusCode;
+
+    let result: Result<Infallible, _> = ErrorMessage::err("No user with id 42")
+        .map_err(|error| error.with_status(StatusCode::NOT_FOUND))
+        .with_err_context("Failed to load the user")
+        .with_err_context("Failed to render the profile");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert_eq!(message.status(), Some(StatusCode::NOT_FOUND));
+    assert_eq!(message.frame_status(), None);
+
+    let message = message.with_status(StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(message.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert_eq!(ErrorMessage::new("No status").context("Still no status").status(), None);
+}
+
+#[cfg(all(feature = "http", feature = "serde"))]
+#[test]
+fn test_status_serde() {
+    let message = ErrorMessage::new("No user with id 42").with_status(http::StatusCode::NOT_FOUND).context("Failed to load the user");
+    let json = serde_json::to_string(&message).expect("Conversion to json failed");
+    println!("test_status_serde():\n{}\n", json);
+    #[cfg(not(feature = "timestamp"))]
+    assert_eq!(json, r#"{"message":"Failed to load the user","cause":{"message":"No user with id 42","status":404,"cause":null}}"#);
+    assert_eq!(message.to_json_string(), json);
+}
+
+#[test]
+fn test_nested_causes_without_downcast() {
+    use std::error::Error;
+
+    // An ErrorMessage passed as a foreign cause joins the chain like any other nested frame
+    let inner = ErrorMessage::with_context("Failed to read file", io::Error::from(ErrorKind::NotFound));
+    let message = ErrorMessage::with_context("Failed to load configuration", inner)
+        .context("Failed to start")
+        .merge(ErrorMessage::new("Failed to delete lock file"));
+    println!("test_nested_causes_without_downcast():\n{}\n", message);
+    assert_eq!(
+        message.to_string(),
+        "Failed to start
+  caused by: Failed to load configuration
+  caused by: Failed to read file
+  caused by: Kind(NotFound)
+  caused by: Failed to delete lock file"
+    );
+    #[cfg(not(any(feature = "timestamp", feature = "pretty_debug_errors")))]
+    assert_eq!(
+        format!("{message:?}"),
+        r#"ErrorMessage { message: "Failed to start", cause: ErrorMessage { message: "Failed to load configuration", cause: ErrorMessage { message: "Failed to read file", cause: ErrorMessage { message: "Kind(NotFound)", cause: ErrorMessage { message: "Failed to delete lock file", cause: None } } } } }"#
+    );
+    #[cfg(not(feature = "timestamp"))]
+    assert_eq!(
+        message.to_json_string(),
+        r#"{"message":"Failed to start","cause":{"message":"Failed to load configuration","cause":{"message":"Failed to read file","cause":{"message":"Kind(NotFound)","cause":{"message":"Failed to delete lock file","cause":null}}}}}"#
+    );
+    assert!(message.source().and_then(|cause| cause.downcast_ref::<ErrorMessage>()).is_some());
+    assert_eq!(message.messages().count(), 5);
+
+    let (frames, leaf) = ErrorMessage::new("I/O Error").with_cause(io::Error::from(ErrorKind::NotFound)).context("Failed to read").into_frames();
+    assert_eq!(frames.len(), 2);
+    let message = ErrorMessage::from_frames(frames, leaf);
+    assert_eq!(message.to_string(), "Failed to read\n  caused by: I/O Error\n  caused by: Kind(NotFound)");
+    assert_eq!(message.downcast::<io::Error>().map(|error| error.kind()).ok(), Some(ErrorKind::NotFound));
+}
+
+#[test]
+fn test_static_messages() {
+    use std::borrow::Cow;
+
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound)).with_err_context_static("Failed to read file");
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert!(matches!(message.message, Cow::Borrowed("Failed to read file")));
+
+    let path = "config.toml";
+    let message = message.context_static(format!("Failed to load '{path}'"));
+    assert!(matches!(&message.message, Cow::Owned(text) if text == "Failed to load 'config.toml'"));
+    println!("test_static_messages():\n{}\n", message);
+    assert_eq!(message.to_string(), "Failed to load 'config.toml'\n  caused by: Failed to read file\n  caused by: Kind(NotFound)");
+
+    let message = None::<Infallible>.with_err_context_static("Missing value").expect_err("Created an error and didn't get an error");
+    assert!(matches!(message.message, Cow::Borrowed("Missing value")));
+    assert!(matches!(ErrorMessage::new_static("Connection refused").message, Cow::Borrowed(_)));
+    assert!(matches!(ErrorMessage::new("Connection refused").message, Cow::Owned(_)));
+    assert_eq!(ErrorMessage::new_static("Connection refused").into_message(), "Connection refused");
+}
+
+#[test]
+fn test_dyn_context_closure_types() {
+    use std::borrow::Cow;
+
+    let code = 2;
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound)).with_dyn_err_context(|| match code {
+        1 => "Failed to read file",
+        _ => "Failed to read backup file",
+    });
+    let message = result.expect_err("Created an error and didn't get an error");
+    assert!(matches!(message.message, Cow::Borrowed("Failed to read backup file")));
+
+    let message = message.dyn_context(|| format!("Failed with code {code}"));
+    assert!(matches!(&message.message, Cow::Owned(text) if text == "Failed with code 2"));
+
+    let cow: Cow<'static, str> = Cow::Borrowed("Missing value");
+    let message = None::<Infallible>.with_dyn_err_context(|| cow).expect_err("Created an error and didn't get an error");
+    assert!(matches!(message.message, Cow::Borrowed("Missing value")));
+    println!("test_dyn_context_closure_types():\n{}\n", message);
+
+    #[cfg(feature = "boolean_errors")]
+    {
+        use crate::BooleanErrors;
+        let message = false.error_dyn_if_false(|| "Condition failed").expect_err("Created an error and didn't get an error");
+        assert!(matches!(message.message, Cow::Borrowed("Condition failed")));
+        let message = true.error_dyn_if_true(|| format!("Condition {code} held")).expect_err("Created an error and didn't get an error");
+        assert_eq!(message.to_string(), "Condition 2 held");
+    }
+}
+
+#[test]
+fn test_typed_context() {
+    use crate::{ContextError, TypedWithContext};
+    use std::error::Error;
+    use std::fmt::{Display, Formatter};
+
+    #[derive(Debug, PartialEq)]
+    enum ConfigError {
+        Missing(&'static str),
+        Invalid,
+    }
+    impl Display for ConfigError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{self:?}")
+        }
+    }
+    impl Error for ConfigError {}
+
+    fn read_port() -> Result<u16, ContextError<ConfigError>> {
+        Err(ConfigError::Missing("port")).with_typed_err_context("Failed to read 'port'")
+    }
+    fn load() -> Result<u16, ContextError<ConfigError>> {
+        read_port().with_dyn_typed_err_context(|| format!("Failed to load '{}'", "config.toml"))
+    }
+
+    let error = load().expect_err("Created an error and didn't get an error");
+    println!("test_typed_context():\n{}\n", error);
+    match &error {
+        ContextError { inner: ConfigError::Missing(key), .. } => assert_eq!(*key, "port"),
+        ContextError { inner: ConfigError::Invalid, .. } => panic!("Got the wrong error"),
+    }
+    assert_eq!(error.context, ["Failed to read 'port'", "Failed to load 'config.toml'"]);
+    assert_eq!(error.to_string(), "Failed to load 'config.toml'\n  caused by: Failed to read 'port'\n  caused by: Missing(\"port\")");
+    assert_eq!(error.source().and_then(|source| source.downcast_ref::<ConfigError>()), Some(&ConfigError::Missing("port")));
+
+    let message = ErrorMessage::from(error);
+    assert_eq!(message.to_string(), "Failed to load 'config.toml'\n  caused by: Failed to read 'port'\n  caused by: Missing(\"port\")");
+    assert_eq!(message.messages().collect::<Vec<_>>(), ["Failed to load 'config.toml'", "Failed to read 'port'", "Missing(\"port\")"]);
+
+    let message = ErrorMessage::from(ContextError::from(ConfigError::Invalid));
+    assert_eq!(message.to_string(), "Invalid\n  caused by: Invalid");
+}
+
+#[test]
+fn test_with_err_context_kv() {
+    let path = Path::new("/etc/app.toml");
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context_kv("Failed to open file", [("path", path.display().to_string()), ("mode", "rw".to_string())])
+        .with_err_context("Failed to load configuration");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_with_err_context_kv():\n{}\n", message);
+    assert_eq!(message.to_string(), r#"Failed to load configuration
+  caused by: Failed to open file
+    path: /etc/app.toml
+    mode: rw
+  caused by: Kind(NotFound)"#);
+    #[cfg(not(feature = "timestamp"))]
+    assert_eq!(
+        message.to_json_string(),
+        r#"{"message":"Failed to load configuration","cause":{"message":"Failed to open file","details":{"path":"/etc/app.toml","mode":"rw"},"cause":{"message":"entity not found","cause":null}}}"#
+    );
+
+    let mut formatted = false;
+    let value: Result<u8, io::Error> = Ok(1);
+    let value = value.with_dyn_err_context_kv(|| "Failed to open file", || {
+        formatted = true;
+        [("path", path.display().to_string())]
+    });
+    assert_eq!(value.ok(), Some(1));
+    assert!(!formatted);
+
+    let message = None::<Infallible>
+        .with_dyn_err_context_kv(|| format!("Failed to open '{}'", path.display()), || [("mode", "rw"), ("attempt", "3")])
+        .expect_err("Created an error and didn't get an error");
+    assert_eq!(message.to_string(), "Failed to open '/etc/app.toml'\n  mode: rw\n  attempt: 3");
+    assert_eq!(message.details(), [("mode".to_string(), "rw".to_string()), ("attempt".to_string(), "3".to_string())]);
+}
+
+#[test]
+fn test_error_codes() {
+    let result: Result<Infallible, _> = Err(io::Error::from(ErrorKind::NotFound))
+        .with_err_context("Failed to read quota file")
+        .with_err_context_code("E1042", "Quota exceeded")
+        .with_err_context("Failed to upload file");
+    let message = result.expect_err("Created an error and didn't get an error");
+
+    println!("test_error_codes():\n{}\n", message);
+    assert_eq!(message.error_code(), Some("E1042"));
+    assert_eq!(message.to_string(), r#"Failed to upload file
+  caused by: [E1042] Quota exceeded
+  caused by: Failed to read quota file
+  caused by: Kind(NotFound)"#);
+    #[cfg(not(feature = "timestamp"))]
+    assert_eq!(
+        message.to_json_string(),
+        r#"{"message":"Failed to upload file","cause":{"message":"Quota exceeded","code":"E1042","cause":{"message":"Failed to read quota file","cause":{"message":"entity not found","cause":null}}}}"#
+    );
+    #[cfg(all(feature = "serde", not(feature = "timestamp")))]
+    assert_eq!(serde_json::to_string(&message).expect("Conversion to json failed"), message.to_json_string());
+
+    let message = message.context("Failed to sync").with_code("E0001").with_id("SYNC001");
+    assert_eq!(message.error_code(), Some("E1042"));
+    assert_eq!(message.to_string().lines().next(), Some("[SYNC001] [E0001] Failed to sync"));
+    assert_eq!(ErrorMessage::new("Connection refused").error_code(), None);
+}
@@ -0,0 +1,57 @@
+use crate::error_message::ErrorMessage;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+impl ErrorMessage {
+    /// Returns the time this frame was created at.
+    ///
+    /// Every frame records it, so it still tells when an error occurred, after the error was serialized and shipped somewhere else.
+    ///
+    /// ```rust
+    /// use std::time::SystemTime;
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused");
+    /// assert!(error.created_at() <= SystemTime::now());
+    /// ```
+    pub fn created_at(&self) -> SystemTime {
+        self.metadata.as_ref().and_then(|metadata| metadata.timestamp).unwrap_or(UNIX_EPOCH)
+    }
+
+    /// The milliseconds since the Unix epoch of [created_at](ErrorMessage::created_at), which is what the serde output contains
+    pub(crate) fn timestamp_millis(&self) -> u64 {
+        since_epoch(self.created_at()).as_millis().try_into().unwrap_or(u64::MAX)
+    }
+}
+
+/// Writes the time in UTC as `2024-05-01T12:30:00.123Z`, which is a valid RFC 3339 timestamp
+pub(crate) fn write_rfc3339<W: fmt::Write>(time: SystemTime, w: &mut W) -> fmt::Result {
+    let since_epoch = since_epoch(time);
+    let seconds = since_epoch.as_secs();
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    // Converts the days since the epoch into a date of the proleptic Gregorian calendar,
+    // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    write!(
+        w,
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Times before the epoch can only come from a clock, that was set wrong, so they are clamped to it
+fn since_epoch(time: SystemTime) -> Duration {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default()
+}
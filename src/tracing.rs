@@ -0,0 +1,55 @@
+use crate::{ErrorMessage, WithContext};
+
+/// [TraceWithContext] is implemented for everything, that implements [WithContext].
+/// It adds the context like [with_err_context](WithContext::with_err_context) and records the new chain as a [tracing] event.
+pub trait TraceWithContext<T, E> {
+    /// Like [with_err_context](WithContext::with_err_context), but in case of an error,
+    /// the whole chain is also recorded as an error event, see [ErrorMessage::record].
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// fn read_config() -> Result<String, io::Error> { Err(io::ErrorKind::NotFound.into()) }
+    ///
+    /// let result = read_config().trace_err_context("Failed to read config");
+    /// # assert_eq!(result.unwrap_err().to_string(), "Failed to read config\n  caused by: Kind(NotFound)");
+    /// ```
+    fn trace_err_context(self, context: impl ToString) -> Result<T, ErrorMessage>;
+}
+
+impl<T, E, R: WithContext<T, E>> TraceWithContext<T, E> for R {
+    #[cfg_attr(feature = "location", track_caller)]
+    fn trace_err_context(self, context: impl ToString) -> Result<T, ErrorMessage> {
+        self.with_err_context(context).inspect_err(ErrorMessage::record)
+    }
+}
+
+impl ErrorMessage {
+    /// Record the chain as an error event with the target `errors_with_context` inside the current span.
+    ///
+    /// The message of the event is the outermost message and these fields describe the whole chain:
+    ///
+    /// | Field         | Value                                                                  |
+    /// |---------------|------------------------------------------------------------------------|
+    /// | `error.chain` | The chain on a single line, like [to_compact_string](ErrorMessage::to_compact_string) |
+    /// | `error.root`  | The last frame of the chain, like it is shown in the Display output   |
+    /// | `error.depth` | The number of frames in the chain, see [depth](ErrorMessage::depth)   |
+    ///
+    /// ```rust
+    /// use errors_with_context::ErrorMessage;
+    /// let error = ErrorMessage::new("Connection refused").context("Failed to sync");
+    /// error.record();
+    /// ```
+    pub fn record(&self) {
+        let root = self.frames().last().expect("A chain always contains at least one frame");
+        tracing::event!(
+            target: "errors_with_context",
+            tracing::Level::ERROR,
+            error.chain = %format_args!("{self:#}"),
+            error.root = %root,
+            error.depth = self.depth(),
+            "{}",
+            self.message
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error_message::{ErrorMessage, Frame};
+
+/// A plain-data copy of one frame of an [ErrorMessage] and everything below it.
+///
+/// Created by [ErrorMessage::to_tree], for example to render an error in a UI without parsing its Display output.
+/// A foreign error at the end of the chain becomes a node with its Display text as the message, just like in the serde output.
+///
+/// Dropping, cloning and comparing nodes walks along the children one after the other, so very deep chains don't overflow the stack.
+#[derive(Debug, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ErrorNode {
+    /// The message of this frame
+    pub message: String,
+    /// The identifier attached with [ErrorMessage::with_id]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub id: Option<String>,
+    /// The error code attached with [ErrorMessage::with_code]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub code: Option<String>,
+    /// The details attached with [ErrorMessage::with_detail], in the order they were added
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub details: Vec<(String, String)>,
+    /// The hints attached with [ErrorMessage::with_hint], in the order they were added
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub hints: Vec<String>,
+    /// Whether this frame was marked with [ErrorMessage::transient]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "std::ops::Not::not"))]
+    pub transient: bool,
+    /// The next frame of the chain
+    pub child: Option<Box<ErrorNode>>,
+    /// The errors combined with [ErrorMessage::from_errors]
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub branches: Vec<ErrorNode>,
+}
+
+impl ErrorMessage {
+    /// Copy the whole chain into a tree of [ErrorNodes](ErrorNode).
+    ///
+    /// ```rust
+    /// # use std::convert::Infallible;
+    /// # use std::io;
+    /// use errors_with_context::prelude::*;
+    /// use errors_with_context::ErrorNode;
+    /// let result: Result<Infallible, _> = Err(io::Error::from(io::ErrorKind::NotFound))
+    ///     .with_err_context("Failed to read file");
+    /// let tree = result.unwrap_err().to_tree();
+    /// assert_eq!(tree.message, "Failed to read file");
+    /// assert_eq!(tree.child.as_ref().unwrap().message, "entity not found");
+    /// ```
+    pub fn to_tree(&self) -> ErrorNode {
+        let mut nodes: Vec<ErrorNode> = self
+            .frames()
+            .map(|frame| match frame {
+                Frame::Message(error) => ErrorNode {
+                    message: error.message.to_string(),
+                    id: error.frame_id().map(str::to_string),
+                    code: error.frame_code().map(str::to_string),
+                    details: error.details().to_vec(),
+                    hints: error.frame_hints().to_vec(),
+                    transient: error.frame_transient(),
+                    child: None,
+                    branches: error.branches().iter().map(ErrorMessage::to_tree).collect(),
+                },
+                Frame::Cause(cause) => ErrorNode { message: cause.to_string(), id: None, code: None, details: Vec::new(), hints: Vec::new(), transient: false, child: None, branches: Vec::new() },
+            })
+            .collect();
+        let mut node = nodes.pop().expect("A chain always contains at least one frame");
+        while let Some(mut parent) = nodes.pop() {
+            parent.child = Some(Box::new(node));
+            node = parent;
+        }
+        node
+    }
+}
+
+impl ErrorNode {
+    /// A copy of this node without its child
+    fn clone_frame(&self) -> ErrorNode {
+        ErrorNode {
+            message: self.message.clone(),
+            id: self.id.clone(),
+            code: self.code.clone(),
+            details: self.details.clone(),
+            hints: self.hints.clone(),
+            transient: self.transient,
+            child: None,
+            branches: self.branches.clone(),
+        }
+    }
+
+    /// Compares the fields of both nodes without their children
+    fn frame_eq(&self, other: &ErrorNode) -> bool {
+        self.message == other.message
+            && self.id == other.id
+            && self.code == other.code
+            && self.details == other.details
+            && self.hints == other.hints
+            && self.transient == other.transient
+            && self.branches == other.branches
+    }
+}
+
+impl Clone for ErrorNode {
+    fn clone(&self) -> ErrorNode {
+        let mut nodes: Vec<ErrorNode> = std::iter::successors(Some(self), |node| node.child.as_deref()).map(ErrorNode::clone_frame).collect();
+        let mut node = nodes.pop().expect("The iterator contains at least this node");
+        while let Some(mut parent) = nodes.pop() {
+            parent.child = Some(Box::new(node));
+            node = parent;
+        }
+        node
+    }
+}
+
+impl PartialEq for ErrorNode {
+    fn eq(&self, other: &ErrorNode) -> bool {
+        let (mut node, mut other) = (self, other);
+        loop {
+            if !node.frame_eq(other) {
+                return false;
+            }
+            match (node.child.as_deref(), other.child.as_deref()) {
+                (Some(child), Some(other_child)) => (node, other) = (child, other_child),
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// Drops the children one after the other, like [ErrorMessage] does
+impl Drop for ErrorNode {
+    fn drop(&mut self) {
+        let mut child = self.child.take();
+        while let Some(mut next) = child {
+            child = next.child.take();
+        }
+    }
+}
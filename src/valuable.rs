@@ -0,0 +1,31 @@
+use crate::error_message::ErrorMessage;
+use valuable::{Fields, NamedField, NamedValues, StructDef, Structable, Valuable, Value, Visit};
+
+static FIELDS: &[NamedField<'static>] =
+    &[NamedField::new("message"), NamedField::new("code"), NamedField::new("details"), NamedField::new("causes")];
+
+/// Exposes `message`, `code`, `details` and `causes`, the list of every frame below the outermost one.
+/// `code` is the [error code](ErrorMessage::error_code) of the chain, or unit without one,
+/// and `details` is the list of the key/value pairs of the outermost frame.
+/// A foreign leaf cause is listed with its Debug text, like in the Display output.
+impl Valuable for ErrorMessage {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visitor: &mut dyn Visit) {
+        let (message, code, details, causes) = (self.message(), self.error_code(), self.details(), causes(self));
+        let values = [message.as_value(), code.as_value(), details.as_value(), causes.as_value()];
+        visitor.visit_named_fields(&NamedValues::new(FIELDS, &values));
+    }
+}
+
+impl Structable for ErrorMessage {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("ErrorMessage", Fields::Named(FIELDS))
+    }
+}
+
+fn causes(error: &ErrorMessage) -> Vec<String> {
+    error.messages().skip(1).map(|message| message.into_owned()).collect()
+}
@@ -0,0 +1,43 @@
+use crate::error_message::ErrorMessage;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HIDE_INTERNALS: AtomicBool = AtomicBool::new(false);
+
+/// Leave the chain out of the error responses of the web framework integrations, for example in production,
+/// so clients only see the outermost message.
+///
+/// The whole chain is still recorded with [record](ErrorMessage::record), if the `tracing` feature is enabled.
+///
+/// ```rust
+/// errors_with_context::hide_error_internals(!cfg!(debug_assertions));
+/// ```
+pub fn hide_error_internals(hide: bool) {
+    HIDE_INTERNALS.store(hide, Ordering::Relaxed);
+}
+
+impl ErrorMessage {
+    /// The JSON body of an error response: `{"error":"<outermost message>","chain":["<message>",...]}`,
+    /// without the chain, if [hide_error_internals] is set
+    pub(crate) fn response_body(&self) -> String {
+        let mut json = String::from("{\"error\":");
+        crate::json::write_string(&self.message, &mut json);
+        if !HIDE_INTERNALS.load(Ordering::Relaxed) {
+            json.push_str(",\"chain\":[");
+            for (index, message) in self.messages().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                crate::json::write_string(&message, &mut json);
+            }
+            json.push(']');
+        }
+        json.push('}');
+        json
+    }
+
+    /// Called for every error, that is turned into a response, because the client doesn't necessarily get to see the chain
+    pub(crate) fn responded(&self) {
+        #[cfg(feature = "tracing")]
+        self.record();
+    }
+}